@@ -0,0 +1,76 @@
+use chess_networking::PromotionPiece;
+use viktoe_chess::{board::Turn, piece::{Color, Piece}, prelude::BoardPosition, ChessGame};
+
+pub mod chess960;
+pub mod draw_rules;
+pub mod fen;
+pub mod notation;
+pub mod result;
+
+pub const PIECE_COUNT : usize = 6;
+pub const PIECE_NAMES : [&str ; PIECE_COUNT] = ["pawn", "bishop", "knight", "rook", "queen", "king"];
+
+/// Index of the piece in PIECE_NAMES and the texture arrays
+pub fn piece_index(piece: &Piece) -> usize {
+     match piece {
+        Piece::Pawn { .. } =>   0,
+        Piece::Bishop =>        1,
+        Piece::Knight =>        2,
+        Piece::Rook =>          3,
+        Piece::Queen =>         4,
+        Piece::King { .. } =>   5,
+    }
+}
+
+/// Piece a pawn can be promoted to, from its index into PIECE_NAMES
+pub fn promotion_piece(index: usize) -> Option<Piece> {
+    match index {
+        1 => Some(Piece::Bishop),
+        2 => Some(Piece::Knight),
+        3 => Some(Piece::Rook),
+        4 => Some(Piece::Queen),
+        _ => None,
+    }
+}
+
+/// Promotion piece as sent to the server, from its index into PIECE_NAMES
+pub fn to_network_promotion(index: usize) -> Option<PromotionPiece> {
+    match index {
+        1 => Some(PromotionPiece::Bishop),
+        2 => Some(PromotionPiece::Knight),
+        3 => Some(PromotionPiece::Rook),
+        4 => Some(PromotionPiece::Queen),
+        _ => None,
+    }
+}
+
+/// Index into PIECE_NAMES of a promotion piece sent by the server
+pub fn from_network_promotion(piece: &PromotionPiece) -> usize {
+    match piece {
+        PromotionPiece::Bishop => 1,
+        PromotionPiece::Knight => 2,
+        PromotionPiece::Rook => 3,
+        PromotionPiece::Queen => 4,
+    }
+}
+
+/// Side (true for white) and index into PIECE_NAMES of the piece on the given slot
+pub fn piece_at(chess: &ChessGame, index: i32) -> Option<(bool, usize)> {
+    match chess.get_square(&index_to_position(index)) {
+        Some(Color::White(piece)) => Some((true, piece_index(piece))),
+        Some(Color::Black(piece)) => Some((false, piece_index(piece))),
+        None => None,
+    }
+}
+
+/// Whether the side to move has a legal move anywhere on the board
+pub fn has_legal_move(chess: &ChessGame) -> bool {
+    let white = matches!(chess.get_player_turn(), Turn::White);
+    (0..64)
+        .filter(|&index| matches!(piece_at(chess, index), Some((side, _)) if side == white))
+        .any(|index| !chess.get_valid_moves(&index_to_position(index)).is_empty())
+}
+
+pub fn index_to_position(index: i32) -> BoardPosition {
+    BoardPosition::try_from((index as u8 % 8, index as u8 / 8)).unwrap()
+}
@@ -0,0 +1,121 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::fen::fen_to_placement;
+
+pub const POSITION_COUNT : u32 = 960;
+/// Index of the standard starting position, RNBQKBNR
+pub const STANDARD_INDEX : u32 = 518;
+
+const ROOK : usize = 3;
+const KING : usize = 5;
+
+// Empty squares, out of the five left after the bishops and queen, that the knights go on
+const KNIGHT_SQUARES : [(usize, usize) ; 10] = [(0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)];
+
+/// White back rank of position `index`, from the a-file to the h-file, using the standard
+/// numbering of the 960 positions
+pub fn back_rank(index: u32) -> [char ; 8] {
+    let mut rank = [' ' ; 8];
+    let mut n = index % POSITION_COUNT;
+
+    // light squared bishop on b, d, f or h, then the dark squared one on a, c, e or g
+    rank[(n % 4 * 2 + 1) as usize] = 'B';
+    n /= 4;
+    rank[(n % 4 * 2) as usize] = 'B';
+    n /= 4;
+
+    let empty : Vec<usize> = (0..8).filter(|&file| rank[file] == ' ').collect();
+    rank[empty[(n % 6) as usize]] = 'Q';
+    n /= 6;
+
+    let empty : Vec<usize> = (0..8).filter(|&file| rank[file] == ' ').collect();
+    let (first, second) = KNIGHT_SQUARES[n as usize];
+    rank[empty[first]] = 'N';
+    rank[empty[second]] = 'N';
+
+    // the king always ends up between the rooks
+    for (file, piece) in (0..8).filter(|&file| rank[file] == ' ').collect::<Vec<_>>().into_iter().zip(['R', 'K', 'R']) {
+        rank[file] = piece;
+    }
+
+    rank
+}
+
+/// FEN record of position `index`, with castling rights written as KQkq for the outermost rooks
+pub fn position_fen(index: u32) -> String {
+    let white : String = back_rank(index).iter().collect();
+    format!("{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1", white.to_ascii_lowercase(), white)
+}
+
+pub fn random_index() -> u32 {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos() ^ d.as_secs() as u32).unwrap_or(0);
+    nanos % POSITION_COUNT
+}
+
+/// King and rook slots each castling right of a placement refers to, as (right, king, rook). A
+/// right is for the outermost rook on its side of the king, so the same rules cover standard
+/// chess and Chess960
+pub fn castling_slots(placement: &str) -> Vec<(char, i32, i32)> {
+    let pieces = fen_to_placement(placement);
+    let mut slots = vec![];
+
+    for (white, rank, [kingside, queenside]) in [(true, 0, ['K', 'Q']), (false, 7, ['k', 'q'])] {
+        let is = |file: i32, piece: usize| pieces[(rank * 8 + file) as usize] == Some((white, piece));
+
+        let Some(king) = (0..8).find(|&file| is(file, KING)) else {
+            continue;
+        };
+
+        if let Some(rook) = (king + 1..8).rev().find(|&file| is(file, ROOK)) {
+            slots.push((kingside, rank * 8 + king, rank * 8 + rook));
+        }
+        if let Some(rook) = (0..king).find(|&file| is(file, ROOK)) {
+            slots.push((queenside, rank * 8 + king, rank * 8 + rook));
+        }
+    }
+
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::fen::START_FEN;
+
+    fn rank(index: u32) -> String {
+        back_rank(index).iter().collect()
+    }
+
+    #[test]
+    fn numbers_positions_the_standard_way() {
+        assert_eq!(rank(0), "BBQNNRKR");
+        assert_eq!(rank(STANDARD_INDEX), "RNBQKBNR");
+        assert_eq!(rank(959), "RKRNNQBB");
+        assert_eq!(position_fen(STANDARD_INDEX), START_FEN);
+    }
+
+    #[test]
+    fn every_position_is_legal_and_different() {
+        let mut ranks = vec![];
+        for index in 0..POSITION_COUNT {
+            let rank = rank(index);
+            let file = |piece| rank.find(piece).unwrap();
+
+            let bishops : Vec<usize> = rank.match_indices('B').map(|(file, _)| file).collect();
+            assert_ne!(bishops[0] % 2, bishops[1] % 2, "{}", rank);
+            assert!(file('R') < file('K') && file('K') < rank.rfind('R').unwrap(), "{}", rank);
+            ranks.push(rank);
+        }
+
+        ranks.sort();
+        ranks.dedup();
+        assert_eq!(ranks.len(), POSITION_COUNT as usize);
+    }
+
+    #[test]
+    fn finds_the_rooks_of_each_castling_right() {
+        assert_eq!(castling_slots("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR"), [('K', 4, 7), ('Q', 4, 0), ('k', 60, 63), ('q', 60, 56)]);
+        // position 0, BBQNNRKR, castles queenside with the rook on the f-file
+        assert_eq!(castling_slots(position_fen(0).split_whitespace().next().unwrap()), [('K', 6, 7), ('Q', 6, 5), ('k', 62, 63), ('q', 62, 61)]);
+    }
+}
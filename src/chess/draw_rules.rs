@@ -0,0 +1,137 @@
+use super::fen::{fen_to_placement, parse_fen};
+use super::notation::square_name;
+use super::result::EndReason;
+
+const PAWN : usize = 0;
+
+// Half-moves without a capture or pawn move, for fifty and seventy-five moves by each side
+const FIFTY_MOVES : u32 = 100;
+const SEVENTY_FIVE_MOVES : u32 = 150;
+
+/// Positions reached in a game and its half-move clock, to tell when a draw can be claimed and
+/// when the game is drawn without a claim
+#[derive(Default)]
+pub struct DrawRules {
+    positions: Vec<String>,
+    halfmove: u32,
+}
+
+impl DrawRules {
+    /// Start counting from the position of `fen`
+    pub fn new(fen: &str) -> Self {
+        let mut rules = Self::default();
+        rules.record(fen);
+        rules
+    }
+
+    /// Count the position of `fen`, reached by the last move
+    pub fn record(&mut self, fen: &str) {
+        let Ok(fields) = parse_fen(fen) else {
+            return;
+        };
+
+        self.halfmove = fields.halfmove;
+        self.positions.push(repetition_key(fen));
+    }
+
+    /// Times the current position has been reached
+    pub fn repetitions(&self) -> usize {
+        match self.positions.last() {
+            Some(current) => self.positions.iter().filter(|&position| position == current).count(),
+            None => 0,
+        }
+    }
+
+    /// Reason a draw can be claimed by the player to move
+    pub fn claimable(&self) -> Option<EndReason> {
+        if self.repetitions() >= 3 {
+            Some(EndReason::ThreefoldRepetition)
+        } else if self.halfmove >= FIFTY_MOVES {
+            Some(EndReason::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    /// Reason the game is drawn without either player claiming it
+    pub fn forced(&self) -> Option<EndReason> {
+        if self.repetitions() >= 5 {
+            Some(EndReason::FivefoldRepetition)
+        } else if self.halfmove >= SEVENTY_FIVE_MOVES {
+            Some(EndReason::SeventyFiveMoveRule)
+        } else {
+            None
+        }
+    }
+}
+
+/// The fields of a FEN record that make positions the same for repetitions: placement, side to
+/// move, castling rights and an en passant capture that can actually be made
+pub fn repetition_key(fen: &str) -> String {
+    let Ok(fields) = parse_fen(fen) else {
+        return fen.to_string();
+    };
+
+    // the square only counts when a pawn of the side to move stands next to the pawn that moved
+    let en_passant = fields.en_passant.filter(|&square| {
+        let pieces = fen_to_placement(&fields.placement);
+        let pawn_rank = if fields.white_to_move { 4 } else { 3 };
+        [-1, 1].iter()
+            .map(|offset| square % 8 + offset)
+            .filter(|file| (0..8).contains(file))
+            .any(|file| pieces[(pawn_rank * 8 + file) as usize] == Some((fields.white_to_move, PAWN)))
+    });
+
+    let side = if fields.white_to_move { "w" } else { "b" };
+    let en_passant = en_passant.map_or("-".to_string(), square_name);
+    format!("{} {} {} {}", fields.placement, side, fields.castling, en_passant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::fen::START_FEN;
+
+    #[test]
+    fn repetition_key_ignores_the_clocks() {
+        assert_eq!(repetition_key(START_FEN), repetition_key("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 12 40"));
+        assert_ne!(repetition_key(START_FEN), repetition_key("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Kkq - 0 1"));
+    }
+
+    #[test]
+    fn repetition_key_keeps_en_passant_only_when_it_can_be_taken() {
+        // after 1. e4 no black pawn stands next to the white one
+        assert_eq!(repetition_key("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq -");
+        // a black pawn on d4 can take on e3
+        assert_eq!(repetition_key("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3"), "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3");
+        // a white pawn on d4 can't
+        assert_eq!(repetition_key("rnbqkbnr/pppppppp/8/8/3PP3/8/PPP2PPP/RNBQKBNR b KQkq e3 0 2"), "rnbqkbnr/pppppppp/8/8/3PP3/8/PPP2PPP/RNBQKBNR b KQkq -");
+    }
+
+    #[test]
+    fn repetitions_can_be_claimed_at_three_and_end_the_game_at_five() {
+        let mut rules = DrawRules::new(START_FEN);
+        rules.record("rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 1 1");
+        assert_eq!(rules.repetitions(), 1);
+
+        for count in 2..=5 {
+            rules.record(START_FEN);
+            assert_eq!(rules.repetitions(), count);
+            assert_eq!(matches!(rules.claimable(), Some(EndReason::ThreefoldRepetition)), count >= 3);
+            assert_eq!(matches!(rules.forced(), Some(EndReason::FivefoldRepetition)), count >= 5);
+        }
+    }
+
+    #[test]
+    fn halfmove_clock_allows_a_claim_at_fifty_moves_and_ends_the_game_at_seventy_five() {
+        let rules = DrawRules::new("8/8/8/4k3/8/8/8/4K2R w - - 99 80");
+        assert!(rules.claimable().is_none());
+
+        let rules = DrawRules::new("8/8/8/4k3/8/8/8/4K2R w - - 100 80");
+        assert!(matches!(rules.claimable(), Some(EndReason::FiftyMoveRule)));
+        assert!(rules.forced().is_none());
+
+        let rules = DrawRules::new("8/8/8/4k3/8/8/8/4K2R w - - 150 105");
+        assert!(matches!(rules.forced(), Some(EndReason::SeventyFiveMoveRule)));
+    }
+}
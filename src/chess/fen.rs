@@ -0,0 +1,194 @@
+use viktoe_chess::ChessGame;
+
+use super::notation::parse_square;
+use super::PIECE_COUNT;
+
+pub const START_FEN : &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+// Indexed the same way as PIECE_NAMES
+const FEN_LETTERS : [char ; PIECE_COUNT] = ['p', 'b', 'n', 'r', 'q', 'k'];
+
+/// A validated FEN record split into its fields
+pub struct FenFields {
+    pub placement: String,
+    pub white_to_move: bool,
+    pub castling: String,
+    pub en_passant: Option<i32>,
+    pub halfmove: u32,
+    pub fullmove: u32,
+}
+
+pub fn parse_fen(fen: &str) -> Result<FenFields, String> {
+    let fields : Vec<&str> = fen.split_whitespace().collect();
+    if fields.len() != 6 {
+        return Err(format!("FEN needs 6 fields, found {}", fields.len()));
+    }
+
+    let ranks : Vec<&str> = fields[0].split('/').collect();
+    if ranks.len() != 8 {
+        return Err("FEN placement needs 8 ranks".to_string());
+    }
+
+    for rank in &ranks {
+        let mut files = 0;
+        for c in rank.chars() {
+            match c {
+                '1'..='8' => files += c as u32 - '0' as u32,
+                _ if FEN_LETTERS.contains(&c.to_ascii_lowercase()) => files += 1,
+                _ => return Err(format!("Unknown piece in FEN: {}", c)),
+            }
+        }
+
+        if files != 8 {
+            return Err(format!("FEN rank does not cover 8 files: {}", rank));
+        }
+    }
+
+    let white_to_move = match fields[1] {
+        "w" => true,
+        "b" => false,
+        side => return Err(format!("Unknown side to move in FEN: {}", side)),
+    };
+
+    let castling = fields[2];
+    if castling != "-" && !castling.chars().all(|c| "KQkq".contains(c)) {
+        return Err(format!("Unknown castling rights in FEN: {}", castling));
+    }
+
+    let en_passant = match fields[3] {
+        "-" => None,
+        square => {
+            let mut chars = square.chars();
+            match (chars.next(), chars.next(), chars.next()) {
+                (Some(file), Some(rank), None) => Some(parse_square(file, rank).ok_or(format!("Unknown en passant square in FEN: {}", square))?),
+                _ => return Err(format!("Unknown en passant square in FEN: {}", square)),
+            }
+        }
+    };
+
+    let halfmove = fields[4].parse().map_err(|_| format!("Invalid halfmove clock in FEN: {}", fields[4]))?;
+    let fullmove = fields[5].parse().map_err(|_| format!("Invalid fullmove number in FEN: {}", fields[5]))?;
+
+    Ok(FenFields {
+        placement: fields[0].to_string(),
+        white_to_move,
+        castling: castling.to_string(),
+        en_passant,
+        halfmove,
+        fullmove,
+    })
+}
+
+/// Piece placement field of a FEN record, `piece_at` giving the side (true for white) and the
+/// index into PIECE_NAMES of the piece on a slot
+pub fn placement_to_fen(piece_at: impl Fn(i32) -> Option<(bool, usize)>) -> String {
+    let mut placement = String::new();
+    for y in (0..8).rev() {
+        let mut empty = 0;
+        for x in 0..8 {
+            match piece_at(y * 8 + x) {
+                Some((white, piece)) => {
+                    if empty != 0 {
+                        placement += &empty.to_string();
+                        empty = 0;
+                    }
+                    placement.push(if white { FEN_LETTERS[piece].to_ascii_uppercase() } else { FEN_LETTERS[piece] });
+                },
+                None => empty += 1,
+            }
+        }
+
+        if empty != 0 {
+            placement += &empty.to_string();
+        }
+        if y != 0 {
+            placement.push('/');
+        }
+    }
+
+    placement
+}
+
+/// Pieces of a validated placement field indexed by slot, as (white, index into PIECE_NAMES)
+pub fn fen_to_placement(placement: &str) -> [Option<(bool, usize)> ; 64] {
+    let mut pieces = [None ; 64];
+
+    for (rank, row) in placement.split('/').enumerate() {
+        let y = 7 - rank as i32;
+        let mut x = 0;
+        for c in row.chars() {
+            if let Some(skip) = c.to_digit(10) {
+                x += skip as i32;
+            } else if let Some(piece) = FEN_LETTERS.iter().position(|&letter| letter == c.to_ascii_lowercase()) {
+                pieces[(y * 8 + x) as usize] = Some((c.is_ascii_uppercase(), piece));
+                x += 1;
+            }
+        }
+    }
+
+    pieces
+}
+
+/// Set up the engine with the position of a FEN record
+pub fn chess_from_fen(fen: &str) -> Result<ChessGame, String> {
+    parse_fen(fen)?;
+
+    if fen == START_FEN {
+        return Ok(ChessGame::default());
+    }
+
+    ChessGame::from_fen(fen).map_err(|_| format!("Position could not be set up: {}", fen))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_fields_of_a_record() {
+        let fields = parse_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b Kq e3 0 1").unwrap();
+
+        assert_eq!(fields.placement, "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR");
+        assert!(!fields.white_to_move);
+        assert_eq!(fields.castling, "Kq");
+        assert_eq!(fields.en_passant, Some(20));
+        assert_eq!((fields.halfmove, fields.fullmove), (0, 1));
+    }
+
+    #[test]
+    fn placement_round_trips() {
+        for fen in [START_FEN, "r3k2r/pp1n1ppp/2p1pn2/q7/1bPP4/2N1PN2/PP1B1PPP/R2QKB1R w KQkq - 3 9", "8/8/8/8/8/8/8/K6k w - - 0 1"] {
+            let fields = parse_fen(fen).unwrap();
+            let pieces = fen_to_placement(&fields.placement);
+            assert_eq!(placement_to_fen(|index| pieces[index as usize]), fields.placement);
+        }
+    }
+
+    #[test]
+    fn places_pieces_by_slot() {
+        let pieces = fen_to_placement(parse_fen(START_FEN).unwrap().placement.as_str());
+
+        // a1 is slot 0 and h8 slot 63, indices follow PIECE_NAMES
+        assert_eq!(pieces[0], Some((true, 3)));
+        assert_eq!(pieces[4], Some((true, 5)));
+        assert_eq!(pieces[12], Some((true, 0)));
+        assert_eq!(pieces[59], Some((false, 4)));
+        assert_eq!(pieces[32], None);
+    }
+
+    #[test]
+    fn rejects_invalid_records() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1",
+            "rnbqkbnr/ppppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQxq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e9 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1",
+        ] {
+            assert!(parse_fen(fen).is_err(), "{}", fen);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use viktoe_chess::{board::Turn, ChessGame};
+
+use super::{index_to_position, piece_at, PIECE_COUNT};
+
+// Indexed the same way as PIECE_NAMES
+pub const PIECE_LETTERS : [&str ; PIECE_COUNT] = ["", "B", "N", "R", "Q", "K"];
+
+const PAWN : usize = 0;
+const KING : usize = 5;
+
+pub fn square_name(index: i32) -> String {
+    format!("{}{}", (b'a' + (index % 8) as u8) as char, index / 8 + 1)
+}
+
+/// Slot index of a square such as "e4", None if it is not on the board
+pub fn parse_square(file: char, rank: char) -> Option<i32> {
+    if !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+        return None;
+    }
+
+    Some((rank as u8 - b'1') as i32 * 8 + (file as u8 - b'a') as i32)
+}
+
+/// Find the move described by `text` in standard algebraic notation among the legal moves of the
+/// side to move. `castle` gives the king move for castling kingside (true) or queenside, None
+/// when castling that way is not allowed
+///
+/// Return the from and to slots and the promotion piece as an index into PIECE_NAMES
+pub fn find_move(chess: &ChessGame, text: &str, castle: impl Fn(bool) -> Option<(i32, i32)>) -> Result<(i32, i32, Option<usize>), String> {
+    let san = text.trim().trim_end_matches(['+', '#', '!', '?']).replace('0', "O");
+
+    if san == "O-O" || san == "O-O-O" {
+        return castle(san == "O-O").map(|(from, to)| (from, to, None)).ok_or(format!("Castling is not allowed: {}", text));
+    }
+
+    let (body, promotion) = match san.split_once('=') {
+        Some((body, promotion)) => (body.to_string(), Some(promotion.to_string())),
+        // also accept promotions written without "=", e.g. e8Q
+        None if san.starts_with(|c: char| c.is_ascii_lowercase()) && san.ends_with(['Q', 'R', 'B', 'N']) => {
+            let (body, promotion) = san.split_at(san.len() - 1);
+            (body.to_string(), Some(promotion.to_string()))
+        },
+        None => (san.clone(), None),
+    };
+
+    let promotion = match promotion {
+        Some(letter) => match PIECE_LETTERS[1..KING].iter().position(|&l| l == letter) {
+            Some(index) => Some(index + 1),
+            None => return Err(format!("Unknown promotion piece: {}", letter)),
+        },
+        None => None,
+    };
+
+    let mut chars : Vec<char> = body.chars().filter(|&c| c != 'x' && c != ':' && c != '-').collect();
+    let piece = match chars.first().and_then(|c| PIECE_LETTERS.iter().position(|l| l.starts_with(*c))) {
+        Some(index) => {
+            chars.remove(0);
+            index
+        },
+        None => PAWN,
+    };
+
+    if chars.len() < 2 || chars.len() > 4 {
+        return Err(format!("Could not read move: {}", text));
+    }
+
+    let (hint, square) = chars.split_at(chars.len() - 2);
+    let to = match parse_square(square[0], square[1]) {
+        Some(to) => to,
+        None => return Err(format!("Could not read move: {}", text)),
+    };
+
+    let file_hint = hint.iter().find(|c| ('a'..='h').contains(c)).map(|&c| (c as u8 - b'a') as i32);
+    let rank_hint = hint.iter().find(|c| ('1'..='8').contains(c)).map(|&c| (c as u8 - b'1') as i32);
+
+    let white = matches!(chess.get_player_turn(), Turn::White);
+    let candidates : Vec<i32> = movers(chess, white, piece, to)
+        .filter(|&index| file_hint.map_or(true, |file| index % 8 == file) && rank_hint.map_or(true, |rank| index / 8 == rank))
+        .collect();
+
+    let from = match candidates[..] {
+        [from] => from,
+        [] => return Err(format!("Illegal move: {}", text)),
+        _ => return Err(format!("Ambiguous move: {}", text)),
+    };
+
+    let promotes = piece == PAWN && (to / 8 == 0 || to / 8 == 7);
+    match (promotes, promotion) {
+        (true, None) => Err(format!("Missing promotion piece: {}", text)),
+        (false, Some(_)) => Err(format!("Only pawns on the last rank can promote: {}", text)),
+        _ => Ok((from, to, promotion)),
+    }
+}
+
+/// File, rank or square of `from` needed to tell its move to `to` apart from the same move by
+/// another piece of the same kind, empty when no other piece can make it
+pub fn disambiguate(chess: &ChessGame, from: i32, to: i32) -> String {
+    let Some((white, piece)) = piece_at(chess, from) else {
+        return String::new();
+    };
+
+    let others : Vec<i32> = movers(chess, white, piece, to).filter(|&index| index != from).collect();
+
+    if others.is_empty() {
+        String::new()
+    } else if others.iter().all(|index| index % 8 != from % 8) {
+        ((b'a' + (from % 8) as u8) as char).to_string()
+    } else if others.iter().all(|index| index / 8 != from / 8) {
+        (from / 8 + 1).to_string()
+    } else {
+        square_name(from)
+    }
+}
+
+/// Slots of the pieces of one side and kind that can legally move to `to`
+fn movers(chess: &ChessGame, white: bool, piece: usize, to: i32) -> impl Iterator<Item = i32> + '_ {
+    let to_pos = index_to_position(to);
+    (0..64)
+        .filter(move |&index| piece_at(chess, index) == Some((white, piece)))
+        .filter(move |&index| chess.get_valid_moves(&index_to_position(index)).get(&to_pos).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::fen::chess_from_fen;
+
+    fn no_castling(_: bool) -> Option<(i32, i32)> {
+        None
+    }
+
+    #[test]
+    fn names_squares_both_ways() {
+        assert_eq!(square_name(0), "a1");
+        assert_eq!(square_name(28), "e4");
+        assert_eq!(square_name(63), "h8");
+        assert_eq!(parse_square('e', '4'), Some(28));
+        assert_eq!(parse_square('i', '4'), None);
+        assert_eq!(parse_square('a', '9'), None);
+    }
+
+    #[test]
+    fn finds_moves_from_the_start() {
+        let chess = ChessGame::default();
+
+        assert_eq!(find_move(&chess, "e4", no_castling), Ok((12, 28, None)));
+        assert_eq!(find_move(&chess, "Nf3", no_castling), Ok((6, 21, None)));
+        // check and annotation marks are ignored
+        assert_eq!(find_move(&chess, "Nc3!?", no_castling), Ok((1, 18, None)));
+    }
+
+    #[test]
+    fn rejects_moves_that_are_not_legal_or_not_notation() {
+        let chess = ChessGame::default();
+
+        assert!(find_move(&chess, "e5", no_castling).unwrap_err().starts_with("Illegal move"));
+        assert!(find_move(&chess, "Nd4", no_castling).unwrap_err().starts_with("Illegal move"));
+        assert!(find_move(&chess, "Ke2", no_castling).unwrap_err().starts_with("Illegal move"));
+        assert!(find_move(&chess, "hello", no_castling).unwrap_err().starts_with("Could not read move"));
+        assert!(find_move(&chess, "O-O", no_castling).unwrap_err().starts_with("Castling is not allowed"));
+        assert!(find_move(&chess, "e4=Q", no_castling).unwrap_err().starts_with("Only pawns on the last rank"));
+    }
+
+    #[test]
+    fn castling_is_left_to_the_caller() {
+        let chess = ChessGame::default();
+        let kingside = |kingside: bool| kingside.then_some((4, 6));
+
+        assert_eq!(find_move(&chess, "O-O", kingside), Ok((4, 6, None)));
+        assert_eq!(find_move(&chess, "0-0", kingside), Ok((4, 6, None)));
+        assert!(find_move(&chess, "O-O-O", kingside).is_err());
+    }
+
+    #[test]
+    fn disambiguates_pieces_of_the_same_kind() {
+        // knights on b1 and f3 can both go to d2
+        let chess = chess_from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+
+        assert!(find_move(&chess, "Nd2", no_castling).unwrap_err().starts_with("Ambiguous move"));
+        assert_eq!(find_move(&chess, "Nbd2", no_castling), Ok((1, 11, None)));
+        assert_eq!(find_move(&chess, "Nfd2", no_castling), Ok((21, 11, None)));
+        assert_eq!(find_move(&chess, "N1d2", no_castling), Ok((1, 11, None)));
+        assert_eq!(disambiguate(&chess, 1, 11), "b");
+        assert_eq!(disambiguate(&chess, 1, 16), "");
+    }
+
+    #[test]
+    fn reads_promotions() {
+        let chess = chess_from_fen("8/P3k3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(find_move(&chess, "a8=Q", no_castling), Ok((48, 56, Some(4))));
+        assert_eq!(find_move(&chess, "a8N", no_castling), Ok((48, 56, Some(2))));
+        assert!(find_move(&chess, "a8", no_castling).unwrap_err().starts_with("Missing promotion piece"));
+        assert!(find_move(&chess, "a8=K", no_castling).unwrap_err().starts_with("Unknown promotion piece"));
+    }
+}
@@ -0,0 +1,62 @@
+pub enum Outcome {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+pub enum EndReason {
+    CheckMate,
+    Stalemate,
+    /// Drawn by the engine for a reason other than stalemate
+    DrawnPosition,
+    Resignation,
+    Timeout,
+    Agreement,
+    ThreefoldRepetition,
+    FiftyMoveRule,
+    FivefoldRepetition,
+    SeventyFiveMoveRule,
+}
+
+pub struct GameResult {
+    pub outcome: Outcome,
+    pub reason: EndReason,
+}
+
+impl GameResult {
+    pub fn new(outcome: Outcome, reason: EndReason) -> Self {
+        Self { outcome, reason }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self.outcome {
+            Outcome::WhiteWins => "White wins",
+            Outcome::BlackWins => "Black wins",
+            Outcome::Draw => "Draw",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self.reason {
+            EndReason::CheckMate => "by checkmate",
+            EndReason::Stalemate => "by stalemate",
+            EndReason::DrawnPosition => "as no one can win",
+            EndReason::Resignation => "by resignation",
+            EndReason::Timeout => "on time",
+            EndReason::Agreement => "by agreement",
+            EndReason::ThreefoldRepetition => "by threefold repetition",
+            EndReason::FiftyMoveRule => "by the fifty-move rule",
+            EndReason::FivefoldRepetition => "by fivefold repetition",
+            EndReason::SeventyFiveMoveRule => "by the seventy-five-move rule",
+        }
+    }
+
+    /// Result as written in the PGN result tag and movetext
+    pub fn score(&self) -> &'static str {
+        match self.outcome {
+            Outcome::WhiteWins => "1-0",
+            Outcome::BlackWins => "0-1",
+            Outcome::Draw => "1/2-1/2",
+        }
+    }
+}
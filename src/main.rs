@@ -1,15 +1,22 @@
 use std::env;
 
+use resources::Resources;
 use scenes::{SceneInitType, SceneStorage};
 use raylib::prelude::*;
 use server::Server;
+use settings::SharedSettings;
 
+mod chess;
+mod resources;
 mod scenes;
 mod ui;
 mod server;
+mod settings;
 
 const WIDTH : i32 = 900;
 const HEIGHT : i32 = 700;
+const MIN_WIDTH : i32 = 720;
+const MIN_HEIGHT : i32 = 560;
 pub const FPS : u32 = 60;
 
 fn main() -> std::io::Result<()> {
@@ -20,21 +27,40 @@ fn main() -> std::io::Result<()> {
         _ => args[1].parse().unwrap(),
     };
 
-    let (mut server, server_running_signal) = Server::init(port)?;
+    let settings = SharedSettings::load();
+
+    let (mut server, server_running_signal, server_errors) = Server::init(port)?;
     let server_thread = std::thread::spawn(move || server.start());
 
     let (mut rl, thread) = raylib::init()
         .size(WIDTH, HEIGHT)
         .title("Chess")
+        .resizable()
         .build();
 
+    // start larger on high-DPI screens, the layout scales with the window from there
+    let dpi_scale = rl.get_window_scale_dpi();
+    rl.set_window_size((WIDTH as f32 * dpi_scale.x) as i32, (HEIGHT as f32 * dpi_scale.y) as i32);
+    rl.set_window_min_size(MIN_WIDTH, MIN_HEIGHT);
+
     rl.set_target_fps(FPS);
-    rl.set_exit_key(Some(KeyboardKey::KEY_DELETE));
-    let mut scene = SceneStorage::new(&mut rl, &thread);
+    // Escape is handled by the scenes and Delete edits text, only closing the window quits
+    rl.set_exit_key(None);
+    let resources = Resources::new();
+    let mut scene = SceneStorage::new(resources.clone(), settings);
     scene.set_scene(SceneInitType::Start, &mut rl, &thread);
 
     while !rl.window_should_close() {
-        let next_scene = scene.update(&mut rl, &thread);
+        for error in server_errors.try_iter() {
+            ui::toast::error(format!("Server: {}", error));
+        }
+
+        // a click that dismisses a toast isn't seen by the scene under it
+        let next_scene = if ui::toast::update(&mut rl, &thread, &resources) {
+            SceneInitType::None
+        } else {
+            scene.update(&mut rl, &thread)
+        };
 
         if !matches!(next_scene, SceneInitType::None) {
             scene.set_scene(next_scene, &mut rl, &thread);
@@ -45,6 +71,7 @@ fn main() -> std::io::Result<()> {
         draw_handler.clear_background(Color::BLACK);
 
         scene.draw(&mut draw_handler);
+        ui::toast::draw(&mut draw_handler);
     }
 
     server_running_signal.store(false, std::sync::atomic::Ordering::SeqCst);
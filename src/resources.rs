@@ -0,0 +1,168 @@
+use std::{cell::{Cell, RefCell}, collections::HashMap, fs, ops::Deref, path::Path, rc::Rc, time::{Duration, Instant, SystemTime}};
+
+use raylib::prelude::*;
+
+use crate::{chess::{PIECE_COUNT, PIECE_NAMES}, scenes::game::{textures::load_piece_set, theme::{load_themes, Theme, THEME_FILE}}, ui::toast};
+
+/// Font used by the widgets when the file exists, the default raylib font otherwise
+pub const UI_FONT : &str = "fonts/ui.ttf";
+
+// How often files are checked for changes when assets are reloaded during development
+const RELOAD_INTERVAL : Duration = Duration::from_secs(1);
+
+/// Asset loaded once and shared by every scene using it. When a newer version is loaded from
+/// disk the old one is marked stale, and stays usable until the scenes holding it fetch the new one
+pub struct Asset<T> {
+    value: T,
+    stale: Cell<bool>,
+}
+
+pub type Shared<T> = Rc<Asset<T>>;
+
+impl<T> Asset<T> {
+    fn shared(value: T) -> Shared<T> {
+        Rc::new(Self { value, stale: Cell::new(false) })
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.stale.get()
+    }
+}
+
+impl<T> Deref for Asset<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// Textures of the pieces of both sides, in the order of `PIECE_NAMES`
+pub struct PieceSet {
+    pub white: [Texture2D ; PIECE_COUNT],
+    pub black: [Texture2D ; PIECE_COUNT],
+}
+
+struct Cached<T> {
+    asset: Shared<T>,
+    // newest modification time of the files it was loaded from
+    modified: Option<SystemTime>,
+}
+
+#[derive(Default)]
+struct Cache {
+    piece_sets: HashMap<String, Cached<PieceSet>>,
+    themes: Option<Cached<Vec<Theme>>>,
+    fonts: HashMap<(String, i32), WeakFont>,
+    last_check: Option<Instant>,
+}
+
+/// Textures, fonts and themes loaded once for the whole run. Created in `main` and handed to the
+/// scenes, cloning it gives another handle to the same cache
+#[derive(Clone, Default)]
+pub struct Resources(Rc<RefCell<Cache>>);
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pieces of the set in `directory`, loaded on the first request
+    pub fn piece_set(&self, rl: &mut RaylibHandle, thread: &RaylibThread, directory: &str) -> Shared<PieceSet> {
+        let mut cache = self.0.borrow_mut();
+        let cached = cache.piece_sets.entry(directory.to_string()).or_insert_with(|| Cached {
+            asset: Asset::shared(load_pieces(rl, thread, directory)),
+            modified: piece_set_modified(directory),
+        });
+        cached.asset.clone()
+    }
+
+    /// Built-in themes and the ones of the theme file, read on the first request
+    pub fn themes(&self) -> Shared<Vec<Theme>> {
+        let mut cache = self.0.borrow_mut();
+        let cached = cache.themes.get_or_insert_with(|| Cached {
+            asset: Asset::shared(load_themes()),
+            modified: modified(Path::new(THEME_FILE)),
+        });
+        cached.asset.clone()
+    }
+
+    /// Font in the TTF file at `path` rasterized at `size`, or the default font when it can't be
+    /// loaded. Fonts are kept until the program exits, so the handle never dangles
+    pub fn font(&self, rl: &mut RaylibHandle, thread: &RaylibThread, path: &str, size: i32) -> WeakFont {
+        let mut cache = self.0.borrow_mut();
+        if let Some(font) = cache.fonts.get(&(path.to_string(), size)) {
+            return font.clone();
+        }
+
+        let font = match rl.load_font_ex(thread, path, size, None) {
+            Ok(font) => unsafe { font.make_weak() },
+            Err(e) => {
+                toast::warning(format!("Could not load the font {}: {}", path, e));
+                rl.get_font_default()
+            },
+        };
+        cache.fonts.insert((path.to_string(), size), font.clone());
+        font
+    }
+
+    /// The UI font rasterized at `size`, without warning about a missing file since it is optional
+    pub fn ui_font(&self, rl: &mut RaylibHandle, thread: &RaylibThread, size: i32) -> WeakFont {
+        if Path::new(UI_FONT).exists() {
+            self.font(rl, thread, UI_FONT, size)
+        } else {
+            rl.get_font_default()
+        }
+    }
+
+    /// Load again the piece sets and themes whose files changed on disk, marking the old ones
+    /// stale. Only done in debug builds, where assets are being worked on
+    pub fn reload_changed(&self, rl: &mut RaylibHandle, thread: &RaylibThread) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        let mut cache = self.0.borrow_mut();
+        if cache.last_check.is_some_and(|last_check| last_check.elapsed() < RELOAD_INTERVAL) {
+            return;
+        }
+        cache.last_check = Some(Instant::now());
+
+        for (directory, cached) in cache.piece_sets.iter_mut() {
+            let modified = piece_set_modified(directory);
+            if modified != cached.modified {
+                cached.asset.stale.set(true);
+                *cached = Cached { asset: Asset::shared(load_pieces(rl, thread, directory)), modified };
+                toast::info(format!("Reloaded the pieces in {}", directory));
+            }
+        }
+
+        if let Some(cached) = &mut cache.themes {
+            let modified = modified(Path::new(THEME_FILE));
+            if modified != cached.modified {
+                cached.asset.stale.set(true);
+                *cached = Cached { asset: Asset::shared(load_themes()), modified };
+                toast::info(format!("Reloaded {}", THEME_FILE));
+            }
+        }
+    }
+}
+
+fn load_pieces(rl: &mut RaylibHandle, thread: &RaylibThread, directory: &str) -> PieceSet {
+    PieceSet {
+        white: load_piece_set(rl, thread, directory, "white"),
+        black: load_piece_set(rl, thread, directory, "black"),
+    }
+}
+
+/// Newest modification time of the textures of a set, None when none of them can be read
+fn piece_set_modified(directory: &str) -> Option<SystemTime> {
+    ["white", "black"].iter()
+        .flat_map(|color| PIECE_NAMES.map(|name| Path::new(directory).join(format!("{}_{}.png", color, name))))
+        .filter_map(|path| modified(&path))
+        .max()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
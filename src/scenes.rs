@@ -1,77 +1,231 @@
-use std::net::TcpStream;
+use std::{cell::Cell, net::TcpStream, rc::Rc};
 
-use end::End;
 use raylib::prelude::*;
-use game::{player::PlayerTypes, Game};
+use game::{player::PlayerTypes, Game, GameSetup};
+use chess960::Chess960;
+use editor::Editor;
+use loadgame::LoadGame;
+use pause::Pause;
+use settings::Settings;
 use remoteconn::RemoteConn;
 use remotegame::RemoteGame;
+use replay::Replay;
 use start::Start;
 
+use crate::{resources::Resources, settings::SharedSettings, ui::animate::{Animate, Easing, Parallel, Repeat, Tween}};
+
 pub mod start;
-mod game;
+pub mod game;
 mod end;
 pub mod remoteconn;
 mod remotegame;
+mod loadgame;
+mod editor;
+mod settings;
+mod replay;
+mod chess960;
+mod pause;
+
+// Darkest the scenes under a pushed one get while it slides in
+const SLIDE_SHADE : f32 = 0.35;
 
 pub enum SceneType {
     None,
     Start(Start),
     RemoteConn(RemoteConn),
     RemoteGame(RemoteGame),
+    LoadGame(LoadGame),
+    Settings(Settings),
+    Editor(Editor),
+    Chess960(Chess960),
+    Replay(Replay),
+    Pause(Pause),
     Game(Game),
-    End(End)
 }
 
 pub enum SceneInitType {
     None,
     Start,
-    RemoteConn,
+    /// Connect to a server, asking it to start from the given FEN
+    RemoteConn(Option<String>),
     RemoteGame(TcpStream),
+    LoadGame,
+    Settings,
+    /// Edit the position of a FEN record, or the standard starting position
+    Editor(Option<String>),
+    /// Pick a Chess960 starting position
+    Chess960,
     Game([PlayerTypes; 2]),
-    End
+    GameFrom([PlayerTypes; 2], GameSetup),
+    /// Step through a loaded game
+    Replay(GameSetup),
+    /// Menu over the running game
+    Pause,
+    /// Put a scene on top of the current one, which is kept as it is underneath
+    Push(Box<SceneInitType>),
+    /// Go back to the scene under the current one
+    Pop,
+}
+
+/// How the scene on top comes in
+enum TransitionKind {
+    /// From black, when the whole stack is replaced
+    Fade,
+    /// Up from the bottom of the window, when a scene is pushed
+    Slide,
 }
 
+struct Transition {
+    kind: TransitionKind,
+    animation: Box<dyn Animate>,
+    // distance left for a sliding scene, as a fraction of the window height
+    offset: Rc<Cell<f32>>,
+    // darkness over the scenes being covered
+    shade: Rc<Cell<f32>>,
+}
+
+/// Scenes stacked on each other. Only the top one is updated, the ones under it are still drawn
+/// as long as the scenes above them are overlays
 pub struct SceneStorage {
-    scene: SceneType,
+    scenes: Vec<SceneType>,
+    transition: Option<Transition>,
+    resources: Resources,
+    settings: SharedSettings,
+}
+
+impl SceneType {
+    fn scene_mut(&mut self) -> Option<&mut dyn Scene> {
+        match self {
+            SceneType::Start(start) => Some(start),
+            SceneType::RemoteConn(rconn) => Some(rconn),
+            SceneType::RemoteGame(rgame) => Some(rgame),
+            SceneType::LoadGame(load) => Some(load),
+            SceneType::Settings(settings) => Some(settings),
+            SceneType::Editor(editor) => Some(editor),
+            SceneType::Chess960(chess960) => Some(chess960),
+            SceneType::Replay(replay) => Some(replay),
+            SceneType::Pause(pause) => Some(pause),
+            SceneType::Game(game) => Some(game),
+            SceneType::None => None,
+        }
+    }
+
+    fn is_overlay(&mut self) -> bool {
+        self.scene_mut().is_some_and(|scene| scene.is_overlay())
+    }
 }
 
 impl SceneStorage {
-    pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread) -> SceneStorage {
+    pub fn new(resources: Resources, settings: SharedSettings) -> SceneStorage {
         SceneStorage {
-            scene: SceneType::None,
+            scenes: vec![],
+            transition: None,
+            resources,
+            settings,
         }
     }
+
     pub fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) {
-        match self.scene {
-            SceneType::Start(ref mut start) => start.draw(draw_handler),
-            SceneType::RemoteConn(ref mut rconn) => rconn.draw(draw_handler),
-            SceneType::RemoteGame(ref mut rgame) => rgame.draw(draw_handler),
-            SceneType::Game(ref mut game) => game.draw(draw_handler),
-            SceneType::End(ref mut end) => end.draw(draw_handler),
-            SceneType::None => {},
+        // nothing under the topmost full scene can be seen
+        let bottom = self.scenes.iter_mut().rposition(|scene| !scene.is_overlay()).unwrap_or(0);
+        let top = self.scenes.len().saturating_sub(1);
+
+        for (index, scene) in self.scenes.iter_mut().enumerate().skip(bottom) {
+            let Some(scene) = scene.scene_mut() else {
+                continue;
+            };
+
+            match &self.transition {
+                Some(Transition { kind: TransitionKind::Slide, offset, shade, .. }) if index == top => {
+                    let (width, height) = (draw_handler.get_screen_width(), draw_handler.get_screen_height());
+                    draw_handler.draw_rectangle(0, 0, width, height, Color::BLACK.fade(shade.get()));
+                    let offset = offset.get() * height as f32;
+                    let camera = Camera2D { offset: Vector2::new(0.0, offset), target: Vector2::zero(), rotation: 0.0, zoom: 1.0 };
+                    let mut mode = draw_handler.begin_mode2D(camera);
+                    scene.draw(&mut mode);
+                },
+                _ => scene.draw(draw_handler),
+            }
+        }
+
+        if let Some(Transition { kind: TransitionKind::Fade, shade, .. }) = &self.transition {
+            let (width, height) = (draw_handler.get_screen_width(), draw_handler.get_screen_height());
+            draw_handler.draw_rectangle(0, 0, width, height, Color::BLACK.fade(shade.get()));
         }
     }
 
     pub fn update(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) -> SceneInitType {
-        match self.scene {
-            SceneType::Start(ref mut start) => start.update(rl, thread),
-            SceneType::RemoteConn(ref mut rconn) => rconn.update(rl, thread),
-            SceneType::RemoteGame(ref mut rgame) => rgame.update(rl, thread),
-            SceneType::Game(ref mut game) => game.update(rl, thread),
-            SceneType::End(ref mut end) => end.update(rl, thread),
-            SceneType::None => { SceneInitType::None },
+        self.resources.reload_changed(rl, thread);
+
+        if let Some(transition) = &mut self.transition {
+            transition.animation.advance(rl.get_frame_time());
+            if transition.animation.is_finished() {
+                self.transition = None;
+            }
+        }
+
+        match self.scenes.last_mut().and_then(|scene| scene.scene_mut()) {
+            Some(scene) => scene.update(rl, thread),
+            None => SceneInitType::None,
         }
     }
 
+    /// Replace every scene with `scene`, or push it or pop the top one
     pub fn set_scene(&mut self, scene: SceneInitType, rl: &mut RaylibHandle, thread: &RaylibThread) {
-        self.scene = match scene {
-            SceneInitType::Start => SceneType::Start(Start::init(rl)),
-            SceneInitType::RemoteConn => SceneType::RemoteConn(RemoteConn::init(rl, thread)),
-            SceneInitType::RemoteGame(stream) => SceneType::RemoteGame(RemoteGame::init(rl, stream)),
-            SceneInitType::Game(players) => SceneType::Game(Game::init(rl, thread, players)),
-            SceneInitType::End => SceneType::End(End::init(rl, thread)),
-            SceneInitType::None => { SceneType::None },
+        match scene {
+            SceneInitType::None => {},
+            SceneInitType::Push(scene) => {
+                let scene = self.init_scene(*scene, rl, thread);
+                self.scenes.push(scene);
+                self.start_transition(TransitionKind::Slide);
+            },
+            SceneInitType::Pop => {
+                self.scenes.pop();
+                self.transition = None;
+                // there is always a scene to go back to
+                if self.scenes.is_empty() {
+                    self.set_scene(SceneInitType::Start, rl, thread);
+                }
+            },
+            scene => {
+                let scene = self.init_scene(scene, rl, thread);
+                self.scenes = vec![scene];
+                self.start_transition(TransitionKind::Fade);
+            },
+        }
+    }
+
+    /// Transitions take as long as other animations, and are skipped when those are turned off
+    fn start_transition(&mut self, kind: TransitionKind) {
+        let seconds = self.settings.get().animation_speed.seconds();
+        let (offset, shade) = match kind {
+            TransitionKind::Fade => (Tween::new(0.0, 0.0, 0.0), Tween::new(1.0, 0.0, seconds).easing(Easing::OutCubic)),
+            // the scenes underneath darken while the new one comes up and brighten back once it is in place
+            TransitionKind::Slide => (
+                Tween::new(1.0, 0.0, seconds).easing(Easing::OutCubic),
+                Tween::new(0.0, SLIDE_SHADE, seconds / 2.0).easing(Easing::InOutSine).yoyo(true).repeat(Repeat::Times(2)),
+            ),
         };
+        let (offset_output, shade_output) = (offset.output(), shade.output());
+        let animation = Parallel::new(vec![Box::new(offset), Box::new(shade)]);
+        self.transition = Some(Transition { kind, animation: Box::new(animation), offset: offset_output, shade: shade_output });
+    }
+
+    fn init_scene(&self, scene: SceneInitType, rl: &mut RaylibHandle, thread: &RaylibThread) -> SceneType {
+        match scene {
+            SceneInitType::Start => SceneType::Start(Start::init(rl, thread, &self.resources)),
+            SceneInitType::RemoteConn(fen) => SceneType::RemoteConn(RemoteConn::init(rl, thread, &self.resources, &self.settings, fen)),
+            SceneInitType::RemoteGame(stream) => SceneType::RemoteGame(RemoteGame::init(rl, thread, &self.resources, &self.settings, stream)),
+            SceneInitType::LoadGame => SceneType::LoadGame(LoadGame::init(rl, thread, &self.resources)),
+            SceneInitType::Settings => SceneType::Settings(Settings::init(rl, thread, &self.resources, &self.settings)),
+            SceneInitType::Editor(fen) => SceneType::Editor(Editor::init(rl, thread, &self.resources, &self.settings, fen)),
+            SceneInitType::Chess960 => SceneType::Chess960(Chess960::init(rl, thread, &self.resources, &self.settings)),
+            SceneInitType::Game(players) => SceneType::Game(Game::init(rl, thread, &self.resources, &self.settings, players)),
+            SceneInitType::GameFrom(players, setup) => SceneType::Game(Game::init_from(rl, thread, &self.resources, &self.settings, players, setup)),
+            SceneInitType::Replay(setup) => SceneType::Replay(Replay::init(rl, thread, &self.resources, &self.settings, setup)),
+            SceneInitType::Pause => SceneType::Pause(Pause::init(rl, thread, &self.resources)),
+            SceneInitType::None | SceneInitType::Push(_) | SceneInitType::Pop => SceneType::None,
+        }
     }
 }
 
@@ -79,6 +233,10 @@ pub trait Scene {
     // fn init(rl: &mut RaylibHandle, thread: &RaylibThread) -> Self;
     fn update(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) -> SceneInitType;
     fn draw(&mut self, draw_handler: &mut RaylibDrawHandle);
+    /// Whether the scenes under this one stay visible while it is on top
+    fn is_overlay(&self) -> bool {
+        false
+    }
 }
 
 
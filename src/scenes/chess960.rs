@@ -0,0 +1,181 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use button::Button;
+
+use super::game::{
+    player::{local::LocalPlayer, PlayerTypes},
+    textures::draw_piece,
+    theme::{selected_theme_index, THEME_COLOR_COUNT},
+    GameSetup,
+};
+use super::{Scene, SceneInitType};
+use crate::{chess::{chess960::{back_rank, position_fen, random_index, POSITION_COUNT}, PIECE_COUNT}, resources::{PieceSet, Resources, Shared}, settings::SharedSettings, ui::{input::Input, layout::{Align, Edges, Layout, Size}, widgets::{dispatch, Event, Widgets}, *}};
+
+const TITLE_FONT_SIZE : i32 = 40;
+const FONT_SIZE : i32 = 25;
+
+const BACK_BUTTON_PADDING : i32 = 10;
+const BACK_BUTTON_SIZE : i32 = 50;
+
+const PREVIEW_TOP : i32 = 110;
+const PREVIEW_TILE_SIZE : i32 = 60;
+
+const CONTROL_WIDTH : i32 = 200;
+const CONTROL_HEIGHT : i32 = 50;
+const CONTROL_SPACING : i32 = 20;
+
+const MESSAGE_FONT_SIZE : i32 = 20;
+
+// Piece letters of the back rank, indexed the same way as PIECE_NAMES
+const PIECE_LETTERS : [char ; PIECE_COUNT] = ['P', 'B', 'N', 'R', 'Q', 'K'];
+
+/// Pick one of the 960 starting positions of Chess960, by number or at random, to start a game from
+pub struct Chess960 {
+    index: u32,
+    pieces: Shared<PieceSet>,
+    colors: [RayColor ; THEME_COLOR_COUNT],
+    message: Option<String>,
+    widgets: Widgets<Self>,
+    title_font: WeakFont,
+    message_font: WeakFont,
+}
+
+impl Scene for Chess960 {
+    fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) {
+        let title = "Chess960";
+        let window_width = draw_handler.get_screen_width();
+        let (width, _) = measure(&self.title_font, title, TITLE_FONT_SIZE);
+        draw_text(draw_handler, &self.title_font, title, (window_width - width) / 2, BACK_BUTTON_PADDING * 3, TITLE_FONT_SIZE, RayColor::WHITE);
+
+        // black's back rank above white's, the way they face each other on the board
+        let rank = back_rank(self.index);
+        let left = preview_left(window_width);
+        for (row, textures) in [&self.pieces.black, &self.pieces.white].into_iter().enumerate() {
+            for (file, letter) in rank.iter().enumerate() {
+                let (px, py) = (left + file as i32 * PREVIEW_TILE_SIZE, PREVIEW_TOP + row as i32 * PREVIEW_TILE_SIZE);
+                draw_handler.draw_rectangle(px, py, PREVIEW_TILE_SIZE, PREVIEW_TILE_SIZE, self.colors[(file + row + 1) & 1]);
+
+                if let Some(piece) = PIECE_LETTERS.iter().position(|l| l == letter) {
+                    draw_piece(draw_handler, &textures[piece], px, py, PREVIEW_TILE_SIZE);
+                }
+            }
+        }
+
+        let caption = format!("Position (0-{})", POSITION_COUNT - 1);
+        draw_text(draw_handler, &self.message_font, &caption, control_left(window_width, 0), control_top(0) - FONT_SIZE - 5, MESSAGE_FONT_SIZE, RayColor::WHITE);
+
+        if let Some(message) = &self.message {
+            let (width, _) = measure(&self.message_font, message, MESSAGE_FONT_SIZE);
+            draw_text(draw_handler, &self.message_font, message, (window_width - width) / 2, control_top(2), MESSAGE_FONT_SIZE, RayColor::WHITE);
+        }
+
+        self.widgets.draw(draw_handler);
+    }
+
+    fn update(&mut self, rl: &mut RaylibHandle, _: &RaylibThread) -> SceneInitType {
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            return SceneInitType::Start;
+        }
+
+        let callbacks = self.widgets.update(rl);
+        dispatch(self, rl, callbacks)
+    }
+}
+
+impl Chess960 {
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, settings: &SharedSettings) -> Self {
+        let index = random_index();
+
+        let themes = resources.themes();
+        let theme = &themes[selected_theme_index(&themes, settings)];
+
+        let mut back_button = Button::new(resources.ui_font(rl, thread, 45));
+        back_button.set_text("<", 45);
+
+        let mut input = Input::init(resources.ui_font(rl, thread, FONT_SIZE), FONT_SIZE);
+        input.set_max_length(3);
+        input.set_text(&index.to_string());
+        input.set_bg_color(RayColor::get_color(0xff_ff_ff_0a));
+        input.set_selected_bg_color(RayColor::get_color(0xff_ff_ff_1a));
+        input.set_border_color(RayColor::get_color(0xff_ff_ff_a0));
+
+        let mut widgets = Widgets::new();
+        let back_button_id = widgets.add(back_button);
+        widgets.on(back_button_id, Event::Clicked, |_, _| SceneInitType::Start);
+
+        // follow the typed number, keeping the last valid position while it is being edited
+        let index_input_id = widgets.add(input);
+        widgets.on(index_input_id, Event::Changed, move |scene: &mut Self, _| {
+            match scene.widgets.text(index_input_id).trim().parse::<u32>() {
+                Ok(index) if index < POSITION_COUNT => {
+                    scene.index = index;
+                    scene.message = None;
+                },
+                _ => scene.message = Some(format!("Pick a position from 0 to {}", POSITION_COUNT - 1)),
+            }
+            SceneInitType::None
+        });
+
+        let random_button_id = widgets.add(create_button(rl, thread, resources, "Random"));
+        widgets.on(random_button_id, Event::Clicked, move |scene: &mut Self, _| {
+            scene.index = random_index();
+            scene.message = None;
+            if let Some(input) = scene.widgets.input_mut(index_input_id) {
+                input.set_text(&scene.index.to_string());
+            }
+            SceneInitType::None
+        });
+
+        let local_button_id = widgets.add(create_button(rl, thread, resources, "Play local"));
+        widgets.on(local_button_id, Event::Clicked, |scene: &mut Self, _| {
+            let setup = GameSetup { fen: Some(position_fen(scene.index)), moves: vec![] };
+            SceneInitType::GameFrom([PlayerTypes::Local(LocalPlayer::init()), PlayerTypes::Local(LocalPlayer::init())], setup)
+        });
+
+        // the server starts both players from the position sent when connecting
+        let remote_button_id = widgets.add(create_button(rl, thread, resources, "Play remote"));
+        widgets.on(remote_button_id, Event::Clicked, |scene: &mut Self, _| SceneInitType::RemoteConn(Some(position_fen(scene.index))));
+
+        // controls sit in two columns under the preview
+        let controls = [[index_input_id, random_button_id], [local_button_id, remote_button_id]]
+            .map(|row| Layout::row(row.map(|id| Layout::widget(id).width(Size::Fixed(CONTROL_WIDTH)))).height(Size::Fixed(CONTROL_HEIGHT)).spacing(CONTROL_SPACING));
+        widgets.set_layout(rl, Layout::overlay([
+            Layout::widget(back_button_id).size(BACK_BUTTON_SIZE, BACK_BUTTON_SIZE).margin(Edges::all(BACK_BUTTON_PADDING)),
+            Layout::column(controls)
+                .width(Size::Fixed(2 * CONTROL_WIDTH + CONTROL_SPACING))
+                .anchor(Align::Center, Align::Start)
+                .margin(Edges::new(0, control_top(0), 0, 0))
+                .spacing(CONTROL_SPACING),
+        ]));
+
+        Chess960 {
+            index,
+            pieces: resources.piece_set(rl, thread, &theme.piece_set),
+            colors: theme.ray_colors(),
+            message: None,
+            widgets,
+            title_font: resources.ui_font(rl, thread, TITLE_FONT_SIZE),
+            message_font: resources.ui_font(rl, thread, MESSAGE_FONT_SIZE),
+        }
+    }
+}
+
+fn create_button(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, text: &'static str) -> Button {
+    let mut button = Button::new(resources.ui_font(rl, thread, FONT_SIZE));
+    button.set_text(text, FONT_SIZE);
+    button
+}
+
+fn preview_left(window_width: i32) -> i32 {
+    (window_width - 8 * PREVIEW_TILE_SIZE) / 2
+}
+
+/// Left of the controls in `column`, for the captions drawn with them
+fn control_left(window_width: i32, column: i32) -> i32 {
+    (window_width - 2 * CONTROL_WIDTH - CONTROL_SPACING) / 2 + column * (CONTROL_WIDTH + CONTROL_SPACING)
+}
+
+fn control_top(row: i32) -> i32 {
+    PREVIEW_TOP + 2 * PREVIEW_TILE_SIZE + 2 * CONTROL_SPACING + FONT_SIZE + row * (CONTROL_HEIGHT + CONTROL_SPACING)
+}
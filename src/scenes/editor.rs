@@ -0,0 +1,419 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use button::Button;
+
+use super::game::{
+    pgn::save_to_file,
+    player::{local::LocalPlayer, PlayerTypes},
+    screen::Screen,
+    textures::draw_piece,
+    theme::{selected_theme_index, THEME_COLOR_COUNT},
+    GameSetup,
+};
+use super::{Scene, SceneInitType};
+use crate::chess::{
+    chess960::castling_slots,
+    fen::{chess_from_fen, fen_to_placement, parse_fen, placement_to_fen, START_FEN},
+    notation::square_name,
+    PIECE_COUNT,
+};
+use crate::{resources::{PieceSet, Resources, Shared}, settings::SharedSettings, ui::{input::Input, layout::{Edges, Layout, Rect, Size}, widgets::{dispatch, Event, Widgets}, *}};
+
+const KING : usize = 5;
+const PAWN : usize = 0;
+
+// Palette of every piece, white then black, in a row above the board
+const PALETTE_SIZE : i32 = 44;
+const PALETTE_PADDING : i32 = 10;
+const PALETTE_HOVERED_COLOR : u32 = 0xff_ff_ff_30;
+
+const COLUMN_PADDING : i32 = 20;
+const COLUMN_WIDTH : i32 = 190;
+const CONTROL_HEIGHT : i32 = 40;
+const CONTROL_SPACING : i32 = 10;
+const CONTROL_FONT_SIZE : i32 = 18;
+const CAPTION_FONT_SIZE : i32 = 16;
+const CAPTION_HEIGHT : i32 = 22;
+
+const CASTLING_LETTERS : [&str ; 4] = ["K", "Q", "k", "q"];
+const CASTLING_ON_COLOR : u32 = 0xeb_c3_34_c0;
+const CASTLING_OFF_COLOR : u32 = 0xff_ff_ff_50;
+
+const BACK_BUTTON_PADDING : i32 = 10;
+const BACK_BUTTON_SIZE : i32 = 50;
+
+const MESSAGE_FONT_SIZE : i32 = 20;
+
+/// Board editor to set up a position, which can be exported as FEN or played from
+pub struct Editor {
+    // (white, index into PIECE_NAMES) of every slot
+    board: [Option<(bool, usize)> ; 64],
+    white_to_move: bool,
+    castling: [bool ; 4],
+    // piece held by the mouse, picked up from the palette or the board
+    dragging: Option<(bool, usize)>,
+    mouse: (i32, i32),
+    // black is drawn at the bottom of the board when set
+    flipped: bool,
+    pieces: Shared<PieceSet>,
+    colors: [RayColor ; THEME_COLOR_COUNT],
+    message: Option<String>,
+    widgets: Widgets<Self>,
+    side_button_id: usize,
+    castling_ids: [usize ; 4],
+    en_passant_id: usize,
+    caption_font: WeakFont,
+    message_font: WeakFont,
+}
+
+/// Buttons that change the position rather than leave the scene
+#[derive(Clone, Copy)]
+enum Edit {
+    SideToMove,
+    Castling(usize),
+    Clear,
+    Reset,
+}
+
+impl Scene for Editor {
+    fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) {
+        let screen = Screen::of(draw_handler);
+        let tile_size = screen.tile_size();
+        for y in 0..8 {
+            for x in 0..8 {
+                let (px, py) = screen.board_coord_to_screen(x, y, self.flipped);
+                draw_handler.draw_rectangle(px, py, tile_size, tile_size, self.colors[((x + y + 1) & 1) as usize]);
+
+                if let Some(piece) = self.board[(y * 8 + x) as usize] {
+                    draw_piece(draw_handler, self.texture(piece), px, py, tile_size);
+                }
+            }
+        }
+
+        let hovered = palette_slot(&screen, self.mouse);
+        for slot in 0..2 * PIECE_COUNT {
+            let (px, py) = palette_position(&screen, slot);
+            if hovered == Some(slot) {
+                draw_handler.draw_rectangle(px, py, PALETTE_SIZE, PALETTE_SIZE, RayColor::get_color(PALETTE_HOVERED_COLOR));
+            }
+            draw_piece(draw_handler, self.texture((slot < PIECE_COUNT, slot % PIECE_COUNT)), px, py, PALETTE_SIZE);
+        }
+
+        let left = COLUMN_PADDING;
+        let top = screen.board_offset_y();
+        draw_text(draw_handler, &self.caption_font, "Side to move", left, top, CAPTION_FONT_SIZE, RayColor::WHITE);
+        draw_text(draw_handler, &self.caption_font, "Castling", left, top + CONTROL_HEIGHT + CONTROL_SPACING + CAPTION_HEIGHT, CAPTION_FONT_SIZE, RayColor::WHITE);
+        draw_text(draw_handler, &self.caption_font, "En passant", left, top + 2 * (CONTROL_HEIGHT + CONTROL_SPACING + CAPTION_HEIGHT), CAPTION_FONT_SIZE, RayColor::WHITE);
+
+        self.widgets.draw(draw_handler);
+
+        if let Some(piece) = self.dragging {
+            draw_piece(draw_handler, self.texture(piece), self.mouse.0 - tile_size / 2, self.mouse.1 - tile_size / 2, tile_size);
+        }
+
+        if let Some(message) = &self.message {
+            let (width, _) = measure(&self.message_font, message, MESSAGE_FONT_SIZE);
+            let y = screen.board_offset_y() + screen.board_size() + PALETTE_PADDING * 2;
+            draw_text(draw_handler, &self.message_font, message, (screen.width() - width) / 2, y, MESSAGE_FONT_SIZE, RayColor::WHITE);
+        }
+    }
+
+    fn update(&mut self, rl: &mut RaylibHandle, _: &RaylibThread) -> SceneInitType {
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            return SceneInitType::Start;
+        }
+
+        self.update_board(rl);
+
+        let callbacks = self.widgets.update(rl);
+        dispatch(self, rl, callbacks)
+    }
+}
+
+impl Editor {
+    /// Start editing the position of `fen`, or the standard starting position
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, settings: &SharedSettings, fen: Option<String>) -> Self {
+        let fields = parse_fen(fen.as_deref().unwrap_or(START_FEN)).or_else(|_| parse_fen(START_FEN)).unwrap();
+
+        let themes = resources.themes();
+        let theme = &themes[selected_theme_index(&themes, settings)];
+
+        let mut widgets = Widgets::new();
+
+        let mut back_button = Button::new(resources.ui_font(rl, thread, 45));
+        back_button.set_text("<", 45);
+        let back_button_id = widgets.add(back_button);
+        widgets.on(back_button_id, Event::Clicked, |_, _| SceneInitType::Start);
+
+        let mut side_button = create_button(rl, thread, resources, "White to play");
+        side_button.set_text(if fields.white_to_move { "White to play" } else { "Black to play" }, CONTROL_FONT_SIZE);
+        let side_button_id = widgets.add(side_button);
+        on_edit(&mut widgets, side_button_id, Edit::SideToMove);
+
+        let mut castling = [false ; 4];
+        let mut castling_ids = [0 ; 4];
+        for (i, letter) in CASTLING_LETTERS.iter().enumerate() {
+            castling[i] = fields.castling.contains(letter);
+            let mut button = Button::new(resources.ui_font(rl, thread, CONTROL_FONT_SIZE));
+            button.set_text(*letter, CONTROL_FONT_SIZE);
+            castling_ids[i] = widgets.add(button);
+            on_edit(&mut widgets, castling_ids[i], Edit::Castling(i));
+        }
+
+        let mut en_passant = Input::init(resources.ui_font(rl, thread, CONTROL_FONT_SIZE), CONTROL_FONT_SIZE);
+        en_passant.set_max_length(2);
+        en_passant.set_placeholder("-");
+        en_passant.set_text(&fields.en_passant.map_or("-".to_string(), square_name));
+        en_passant.set_bg_color(RayColor::get_color(0xff_ff_ff_0a));
+        en_passant.set_selected_bg_color(RayColor::get_color(0xff_ff_ff_1a));
+        en_passant.set_border_color(RayColor::get_color(0xff_ff_ff_a0));
+        let en_passant_id = widgets.add(en_passant);
+        // enter in the en passant input checks the position
+        widgets.on(en_passant_id, Event::Submitted, |scene: &mut Self, _| {
+            if let Err(e) = scene.to_fen() {
+                scene.message = Some(e);
+            }
+            SceneInitType::None
+        });
+
+        let clear_button_id = widgets.add(create_button(rl, thread, resources, "Clear"));
+        on_edit(&mut widgets, clear_button_id, Edit::Clear);
+        let reset_button_id = widgets.add(create_button(rl, thread, resources, "Reset board"));
+        on_edit(&mut widgets, reset_button_id, Edit::Reset);
+
+        let export_button_id = widgets.add(create_button(rl, thread, resources, "Export FEN"));
+        widgets.on(export_button_id, Event::Clicked, |scene: &mut Self, _| {
+            scene.export();
+            SceneInitType::None
+        });
+        let local_button_id = widgets.add(create_button(rl, thread, resources, "Play local"));
+        widgets.on(local_button_id, Event::Clicked, |scene: &mut Self, _| match scene.checked_fen() {
+            Some(fen) => SceneInitType::GameFrom([PlayerTypes::Local(LocalPlayer::init()), PlayerTypes::Local(LocalPlayer::init())], GameSetup { fen: Some(fen), moves: vec![] }),
+            None => SceneInitType::None,
+        });
+        let remote_button_id = widgets.add(create_button(rl, thread, resources, "Play remote"));
+        widgets.on(remote_button_id, Event::Clicked, |scene: &mut Self, _| match scene.checked_fen() {
+            Some(fen) => SceneInitType::RemoteConn(Some(fen)),
+            None => SceneInitType::None,
+        });
+
+        // controls on the left of the board edit the position under their captions, the ones on the
+        // right leave the scene
+        let control = |id| Layout::widget(id).height(Size::Fixed(CONTROL_HEIGHT));
+        let caption = Edges::new(0, CAPTION_HEIGHT, 0, 0);
+        let left = Layout::column([
+            control(side_button_id).margin(caption),
+            Layout::row(castling_ids.map(Layout::widget)).height(Size::Fixed(CONTROL_HEIGHT)).spacing(CONTROL_SPACING).margin(caption),
+            control(en_passant_id).margin(caption),
+            control(clear_button_id).margin(Edges::new(0, CONTROL_SPACING, 0, 0)),
+            control(reset_button_id),
+        ]).spacing(CONTROL_SPACING);
+        let right = Layout::column([export_button_id, local_button_id, remote_button_id].map(control)).spacing(CONTROL_SPACING);
+
+        widgets.set_layout(rl, Layout::overlay([
+            Layout::widget(back_button_id).size(BACK_BUTTON_SIZE, BACK_BUTTON_SIZE).margin(Edges::all(BACK_BUTTON_PADDING)),
+            Layout::within(|window| {
+                let screen = Screen::new(window.width, window.height);
+                Rect::new(COLUMN_PADDING, screen.board_offset_y(), COLUMN_WIDTH, screen.board_size())
+            }, left),
+            Layout::within(|window| {
+                let screen = Screen::new(window.width, window.height);
+                Rect::new(window.right() - COLUMN_PADDING - COLUMN_WIDTH, screen.board_offset_y(), COLUMN_WIDTH, screen.board_size())
+            }, right),
+        ]));
+
+        let mut editor = Editor {
+            board: fen_to_placement(&fields.placement),
+            white_to_move: fields.white_to_move,
+            castling,
+            dragging: None,
+            mouse: (0, 0),
+            flipped: false,
+            pieces: resources.piece_set(rl, thread, &theme.piece_set),
+            colors: theme.ray_colors(),
+            message: None,
+            widgets,
+            side_button_id,
+            castling_ids,
+            en_passant_id,
+            caption_font: resources.ui_font(rl, thread, CAPTION_FONT_SIZE),
+            message_font: resources.ui_font(rl, thread, MESSAGE_FONT_SIZE),
+        };
+
+        editor.update_castling_colors();
+
+        editor
+    }
+
+    fn texture(&self, (white, piece): (bool, usize)) -> &Texture2D {
+        if white { &self.pieces.white[piece] } else { &self.pieces.black[piece] }
+    }
+
+    /// Drag pieces from the palette or around the board, dropping one outside the board removes it
+    fn update_board(&mut self, rl: &mut RaylibHandle) {
+        let screen = Screen::of(rl);
+        self.mouse = (rl.get_mouse_x(), rl.get_mouse_y());
+        let slot = board_slot(&screen, self.mouse, self.flipped);
+
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            if let Some(palette) = palette_slot(&screen, self.mouse) {
+                self.dragging = Some((palette < PIECE_COUNT, palette % PIECE_COUNT));
+            } else if let Some(slot) = slot {
+                self.dragging = self.board[slot].take();
+            }
+        }
+
+        if rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT) {
+            if let (Some(piece), Some(slot)) = (self.dragging, slot) {
+                self.board[slot] = Some(piece);
+            }
+            self.dragging = None;
+        }
+
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT) {
+            if let Some(slot) = slot {
+                self.board[slot] = None;
+            }
+        }
+    }
+
+    fn apply_edit(&mut self, edit: Edit) {
+        match edit {
+            Edit::SideToMove => {
+                self.white_to_move = !self.white_to_move;
+                if let Some(button) = self.widgets.button_mut(self.side_button_id) {
+                    button.set_text(if self.white_to_move { "White to play" } else { "Black to play" }, CONTROL_FONT_SIZE);
+                }
+            },
+            Edit::Castling(i) => self.castling[i] = !self.castling[i],
+            Edit::Clear => {
+                self.board = [None ; 64];
+                self.castling = [false ; 4];
+            },
+            Edit::Reset => {
+                let fields = parse_fen(START_FEN).unwrap();
+                self.board = fen_to_placement(&fields.placement);
+                self.castling = [true ; 4];
+            },
+        }
+
+        self.update_castling_colors();
+        self.message = None;
+    }
+
+    fn update_castling_colors(&mut self) {
+        for i in 0..CASTLING_LETTERS.len() {
+            if let Some(button) = self.widgets.button_mut(self.castling_ids[i]) {
+                button.set_color(RayColor::get_color(if self.castling[i] { CASTLING_ON_COLOR } else { CASTLING_OFF_COLOR }));
+            }
+        }
+    }
+
+    /// The edited position as FEN, or why it can't be played
+    fn to_fen(&self) -> Result<String, String> {
+        for (white, side) in [(true, "White"), (false, "Black")] {
+            let kings = self.board.iter().filter(|&&piece| piece == Some((white, KING))).count();
+            if kings != 1 {
+                return Err(format!("{} needs exactly one king, found {}", side, kings));
+            }
+        }
+
+        if (0..8).chain(56..64).any(|slot| matches!(self.board[slot], Some((_, PAWN)))) {
+            return Err("Pawns can't stand on the first or last rank".to_string());
+        }
+
+        // kings and rooks may start on any file as in Chess960, a right is for the outermost rook
+        // on its side of the king
+        let placement = placement_to_fen(|index| self.board[index as usize]);
+        let slots = castling_slots(&placement);
+        let mut castling = String::new();
+        for (i, letter) in CASTLING_LETTERS.iter().enumerate() {
+            if !self.castling[i] {
+                continue;
+            }
+
+            if !slots.iter().any(|&(right, _, _)| letter.starts_with(right)) {
+                return Err(format!("Castling {} needs the king on its back rank and a rook on that side", letter));
+            }
+            castling += letter;
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant = match self.widgets.text(self.en_passant_id).trim() {
+            "" => "-".to_string(),
+            text => text.to_string(),
+        };
+
+        let fen = format!("{} {} {} {} 0 1", placement, if self.white_to_move { "w" } else { "b" }, castling, en_passant);
+        let fields = parse_fen(&fen)?;
+
+        if let Some(square) = fields.en_passant {
+            // the pawn that just moved two squares stands in front of the square it skipped
+            let (rank, pawn) = if self.white_to_move { (5, square - 8) } else { (2, square + 8) };
+            if square / 8 != rank || self.board[square as usize].is_some() || self.board[pawn as usize] != Some((!self.white_to_move, PAWN)) {
+                return Err(format!("No pawn can be taken en passant on {}", en_passant));
+            }
+        }
+
+        chess_from_fen(&fen)?;
+        Ok(fen)
+    }
+
+    /// The position as FEN, reporting why it can't be played instead when it is invalid
+    fn checked_fen(&mut self) -> Option<String> {
+        match self.to_fen() {
+            Ok(fen) => Some(fen),
+            Err(e) => {
+                self.message = Some(e);
+                None
+            }
+        }
+    }
+
+    fn export(&mut self) {
+        let Some(fen) = self.checked_fen() else {
+            return;
+        };
+
+        self.message = Some(match save_to_file(&fen, "fen") {
+            Ok(path) => format!("{} saved to {}", fen, path.display()),
+            Err(e) => format!("{} could not be saved: {}", fen, e),
+        });
+    }
+}
+
+/// Change the position with `edit` when the button `id` is clicked
+fn on_edit(widgets: &mut Widgets<Editor>, id: usize, edit: Edit) {
+    widgets.on(id, Event::Clicked, move |scene: &mut Editor, _| {
+        scene.apply_edit(edit);
+        SceneInitType::None
+    });
+}
+
+fn create_button(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, text: &'static str) -> Button {
+    let mut button = Button::new(resources.ui_font(rl, thread, CONTROL_FONT_SIZE));
+    button.set_text(text, CONTROL_FONT_SIZE);
+    button
+}
+
+/// Top left of a palette slot, white pieces first
+fn palette_position(screen: &Screen, slot: usize) -> (i32, i32) {
+    let width = 2 * PIECE_COUNT as i32 * PALETTE_SIZE;
+    let left = screen.board_offset_x() + (screen.board_size() - width) / 2;
+    (left + slot as i32 * PALETTE_SIZE, screen.board_offset_y() - PALETTE_SIZE - PALETTE_PADDING)
+}
+
+fn palette_slot(screen: &Screen, (x, y): (i32, i32)) -> Option<usize> {
+    (0..2 * PIECE_COUNT).find(|&slot| {
+        let (px, py) = palette_position(screen, slot);
+        (px..px + PALETTE_SIZE).contains(&x) && (py..py + PALETTE_SIZE).contains(&y)
+    })
+}
+
+fn board_slot(screen: &Screen, (x, y): (i32, i32), flipped: bool) -> Option<usize> {
+    let (x, y) = screen.screen_to_board_coord(x, y, flipped);
+    if x < 0 { None } else { Some((y * 8 + x) as usize) }
+}
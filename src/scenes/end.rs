@@ -1,17 +1,133 @@
 use raylib::prelude::*;
+use raylib::color::Color as RayColor;
 
-use super::{Scene, SceneInitType, SceneStorage, SceneType};
+use crate::{chess::result::GameResult, resources::Resources, ui::{button::Button, draw_text, label::Label, layout::{Align, Edges, Layout, Size}, measure, widgets::{dispatch, Event, Widgets}}};
 
-pub struct End {}
+use super::{game::{pgn::save_to_file, player::PlayerTypes, GameSetup}, Scene, SceneInitType};
+
+const PANEL_WIDTH : i32 = 360;
+const PANEL_HEIGHT : i32 = 320;
+const PANEL_COLOR : u32 = 0x10_10_10_d0;
+
+const TITLE_FONT_SIZE : i32 = 40;
+const DESCRIPTION_FONT_SIZE : i32 = 25;
+const MESSAGE_FONT_SIZE : i32 = 15;
+// Room for two lines of message under the buttons
+const MESSAGE_HEIGHT : i32 = 2 * MESSAGE_FONT_SIZE + MESSAGE_FONT_SIZE / 4;
+const TEXT_PADDING : i32 = 20;
+
+// Distance from the top of the panel to the first button
+const BUTTONS_OFFSET : i32 = TEXT_PADDING * 3 + TITLE_FONT_SIZE + DESCRIPTION_FONT_SIZE;
+const BUTTON_WIDTH : i32 = 260;
+const BUTTON_HEIGHT : i32 = 45;
+const BUTTON_SPACING : i32 = 10;
+const BUTTON_FONT_SIZE : i32 = 30;
+
+/// Overlay drawn on top of the final position of a finished game
+pub struct End {
+    result: GameResult,
+    pgn: String,
+    // players and start position of the next game, None when the game can't be restarted here
+    rematch: Option<([PlayerTypes; 2], GameSetup)>,
+    widgets: Widgets<Self>,
+    message_id: usize,
+    title_font: WeakFont,
+    description_font: WeakFont,
+}
 
 impl Scene for End {
-    fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) { }
-    fn update(&mut self, rl: &mut raylib::RaylibHandle, thread: &RaylibThread) -> SceneInitType { SceneInitType::None }
+    fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) {
+        let top = panel_top(draw_handler.get_screen_height());
+        draw_handler.draw_rectangle((draw_handler.get_screen_width() - PANEL_WIDTH) / 2, top, PANEL_WIDTH, PANEL_HEIGHT, RayColor::get_color(PANEL_COLOR));
+
+        let title_y = top + TEXT_PADDING;
+        let description_y = title_y + TITLE_FONT_SIZE + TEXT_PADDING / 2;
+        draw_centered_text(draw_handler, &self.title_font, self.result.title(), title_y, TITLE_FONT_SIZE);
+        draw_centered_text(draw_handler, &self.description_font, self.result.description(), description_y, DESCRIPTION_FONT_SIZE);
+
+        self.widgets.draw(draw_handler);
+    }
+
+    fn update(&mut self, rl: &mut raylib::RaylibHandle, _: &RaylibThread) -> SceneInitType {
+        let callbacks = self.widgets.update(rl);
+        dispatch(self, rl, callbacks)
+    }
 }
 
 impl End {
-    pub fn init(rl: &mut raylib::RaylibHandle, thread: &raylib::RaylibThread) -> Self {
-        Self {}
+    /// `pgn` is the finished game, `rematch` the players and position a rematch starts with, None
+    /// for games that cannot be restarted from here (i.e. remote games)
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, result: GameResult, pgn: String, rematch: Option<([PlayerTypes; 2], GameSetup)>) -> Self {
+        let mut widgets = Widgets::new();
+
+        let menu_button_id = widgets.add(create_button(rl, thread, resources, "Main menu"));
+        widgets.on(menu_button_id, Event::Clicked, |_, _| SceneInitType::Start);
+
+        let mut rematch_button = create_button(rl, thread, resources, "Rematch");
+        rematch_button.set_enabled(rematch.is_some());
+        let rematch_button_id = widgets.add(rematch_button);
+        widgets.on(rematch_button_id, Event::Clicked, |scene: &mut Self, _| match scene.rematch.take() {
+            Some((players, setup)) => SceneInitType::GameFrom(players, setup),
+            None => SceneInitType::None,
+        });
+
+        let save_button_id = widgets.add(create_button(rl, thread, resources, "Save PGN"));
+        widgets.on(save_button_id, Event::Clicked, |scene: &mut Self, _| {
+            scene.save();
+            SceneInitType::None
+        });
+
+        // long paths are wrapped, keeping the last line at the bottom of the panel
+        let mut message = Label::new(resources.ui_font(rl, thread, MESSAGE_FONT_SIZE), MESSAGE_FONT_SIZE);
+        message.set_color(RayColor::WHITE);
+        message.set_wrap(true);
+        message.set_align(Align::Center, Align::End);
+        let message_id = widgets.add(message);
+
+        // the buttons are stacked in the panel, under the title and description, with the message
+        // at its bottom
+        let buttons = [menu_button_id, rematch_button_id, save_button_id].map(|id| Layout::widget(id).size(BUTTON_WIDTH, BUTTON_HEIGHT).anchor(Align::Center, Align::Start));
+        widgets.set_layout(rl, Layout::overlay([
+            Layout::column(buttons).padding(Edges::new(0, BUTTONS_OFFSET, 0, 0)).spacing(BUTTON_SPACING),
+            Layout::widget(message_id).height(Size::Fixed(MESSAGE_HEIGHT)).anchor(Align::Start, Align::End).margin(Edges::new(TEXT_PADDING, 0, TEXT_PADDING, TEXT_PADDING / 4)),
+        ])
+            .size(PANEL_WIDTH, PANEL_HEIGHT)
+            .anchor(Align::Center, Align::Center));
+
+        End {
+            result,
+            pgn,
+            rematch,
+            widgets,
+            message_id,
+            title_font: resources.ui_font(rl, thread, TITLE_FONT_SIZE),
+            description_font: resources.ui_font(rl, thread, DESCRIPTION_FONT_SIZE),
+        }
+    }
+
+    fn save(&mut self) {
+        let message = match save_to_file(&self.pgn, "pgn") {
+            Ok(path) => format!("Saved to {}", path.display()),
+            Err(e) => format!("Could not save game: {}", e),
+        };
+        if let Some(label) = self.widgets.label_mut(self.message_id) {
+            label.set_text(message);
+        }
     }
 }
 
+fn create_button(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, text: &'static str) -> Button {
+    let mut button = Button::new(resources.ui_font(rl, thread, BUTTON_FONT_SIZE));
+    button.set_text(text, BUTTON_FONT_SIZE);
+    button
+}
+
+fn panel_top(window_height: i32) -> i32 {
+    (window_height - PANEL_HEIGHT) / 2
+}
+
+fn draw_centered_text(draw_handler: &mut RaylibDrawHandle, font: &WeakFont, text: &str, y: i32, font_size: i32) {
+    let (width, _) = measure(font, text, font_size);
+    let left = (draw_handler.get_screen_width() - width) / 2;
+    draw_text(draw_handler, font, text, left, y, font_size, RayColor::WHITE);
+}
@@ -2,58 +2,237 @@ use crate::ui::animate::*;
 use player::{Player, PlayerTypes};
 use raylib::prelude::*;
 use raylib::color::Color as RayColor;
-use viktoe_chess::{board::{GameState, Turn}, piece::Color, prelude::BoardPosition, ChessGame};
+use viktoe_chess::{board::{GameState, Turn}, piece::Color, ChessGame};
 
-use super::{Scene, SceneInitType, SceneStorage};
+use super::{end::End, Scene, SceneInitType, SceneStorage};
 
-use crate::FPS;
+use crate::ui::{button::Button, draw_text, input::Input, measure, UIElementTrait};
+use crate::{resources::{PieceSet, Resources, Shared}, settings::{Orientation, SharedSettings}};
 
-mod screen;
+pub mod screen;
+use screen::Screen;
 
-mod textures;
+pub mod textures;
 use textures::*;
 
 mod components;
+mod annotations;
+use annotations::Annotation;
+mod captures;
 mod promotion;
+pub mod notation;
+use notation::MoveRecord;
+
 pub mod player;
+pub mod pgn;
+mod fen;
+mod castling;
+pub mod theme;
+use theme::{select_theme, selected_theme_index, Theme, THEME_COLOR_COUNT};
+use pgn::save_to_file;
+use crate::chess::{draw_rules::DrawRules, fen::{chess_from_fen, START_FEN}, has_legal_move, index_to_position, piece_at, result::{EndReason, GameResult, Outcome}};
+
+const SIDE_BUTTON_LEFT : i32 = 30;
+const SIDE_BUTTON_WIDTH : i32 = 150;
+const SIDE_BUTTON_HEIGHT : i32 = 45;
+const SIDE_BUTTON_SPACING : i32 = 15;
+const SIDE_BUTTON_FONT_SIZE : i32 = 25;
+const SIDE_BUTTON_COUNT : i32 = 6;
+
+const MESSAGE_FONT_SIZE : i32 = 20;
+const MESSAGE_TOP_PADDING : i32 = 15;
 
-const WHITE_SLOT_COLOR : u32 = 0xedd6b0ff;
-const BLACK_SLOT_COLOR : u32 = 0xb88762ff;
-const SELECTED_SLOT_COLOR : u32 = 0xdbc34aff;
-const ATTACKABLE_SLOT_COLOR : u32 = 0x05050540;
-const TURN_VISUAL_COLOR : u32 = 0xebc334ff;
-const KING_SQUARE_IN_CHECK : u32 = 0xf55742ff;
+const MOVE_INPUT_OFFSET : i32 = 70;
+const MOVE_INPUT_WIDTH : i32 = 160;
+const MOVE_INPUT_HEIGHT : i32 = 30;
+const MOVE_INPUT_FONT_SIZE : i32 = 20;
+
+/// Position to start a game from and the moves, in algebraic notation, to replay on top of it
+#[derive(Default, Clone)]
+pub struct GameSetup {
+    pub fen: Option<String>,
+    pub moves: Vec<String>,
+}
 
 pub struct Game {
     chess: ChessGame,
-    white_textures: [Texture2D ; PIECE_COUNT],
-    black_textures: [Texture2D ; PIECE_COUNT],
-    colors: [RayColor ; 6],
+    start_fen: String,
+    resources: Resources,
+    settings: SharedSettings,
+    pieces: Shared<PieceSet>,
+    colors: [RayColor ; THEME_COLOR_COUNT],
+    themes: Shared<Vec<Theme>>,
+    theme: usize,
     players: [PlayerTypes; 2],
     player_turn: Turn,
     promoted_slot: Option<i32>,
     king_index: (i32, i32),
-    animation: Animation,
+    animation: Tween<f32>,
+    // squares the piece of the last move slides between, and how far along it is
+    moving: Option<(i32, i32)>,
+    move_animation: Tween<f32>,
+    // [taken by white, taken by black] as indices into the texture arrays
+    captured: [Vec<usize>; 2],
+    history: Vec<MoveRecord>,
+    draw_rules: DrawRules,
+    annotations: Vec<Annotation>,
+    annotation_start: Option<i32>,
+    // window the board is laid out in, as of the last update or draw
+    screen: Screen,
+    show_coordinates: bool,
+    // black is drawn at the bottom of the board when set
+    flipped: bool,
+    confirm_moves: bool,
+    // legal move made on this computer that waits for confirmation before it is played
+    pending_move: Option<(i32, i32)>,
+    resign_button: Button,
+    draw_button: Button,
+    claim_button: Button,
+    save_pgn_button: Button,
+    save_fen_button: Button,
+    edit_button: Button,
+    move_input: Input,
+    // promotion piece of a typed move, or of a remote move picked before it is sent, used once
+    // the engine asks for it
+    typed_promotion: Option<usize>,
+    // move of a remote player waiting for the piece its pawn promotes to
+    promotion_move: Option<(i32, i32)>,
+    message: Option<String>,
+    // UI font at the sizes the board and the message are drawn with
+    message_font: WeakFont,
+    coordinate_font: WeakFont,
+    capture_font: WeakFont,
+    end: Option<End>,
 }
 
 impl Scene for Game {
     fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) {
-        self.draw_board_background(draw_handler);
-        self.draw_special_state(draw_handler);
-        self.draw_attackable_slots(draw_handler);
-        self.draw_pieces_on_board(draw_handler);
-        self.draw_player_turn_bar(draw_handler);
+        self.draw_board(draw_handler);
+
+        if let Some(end) = &mut self.end {
+            end.draw(draw_handler);
+        } else {
+            self.resign_button.draw(draw_handler);
+            self.draw_button.draw(draw_handler);
+            self.claim_button.draw(draw_handler);
+            self.save_pgn_button.draw(draw_handler);
+            self.save_fen_button.draw(draw_handler);
+            self.edit_button.draw(draw_handler);
+            self.move_input.draw(draw_handler);
+        }
+
+        self.draw_message(draw_handler);
     }
 
-    fn update(&mut self, rl: &mut RaylibHandle, _: &RaylibThread) -> SceneInitType {
+    fn update(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) -> SceneInitType {
+        self.layout(rl);
+        self.refresh_resources(rl, thread);
+        self.advance_animations(rl.get_frame_time());
+
+        if let Some(end) = &mut self.end {
+            return end.update(rl, thread);
+        }
+
+        self.update_annotations(rl);
+
+        // shortcuts would otherwise fire while typing a move
+        if !self.move_input.is_selected() {
+            if rl.is_key_pressed(KeyboardKey::KEY_F) {
+                self.flipped = !self.flipped;
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_C) {
+                self.show_coordinates = !self.show_coordinates;
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_T) {
+                self.set_theme(rl, thread, (self.theme + 1) % self.themes.len());
+                self.message = Some(format!("Theme: {}", self.themes[self.theme].name));
+            }
+            // Escape takes back a move waiting for confirmation before it pauses, a game against
+            // someone over the network goes on and can't be paused
+            if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) && self.is_local() && self.pending_move.is_none() && self.promotion_move.is_none() {
+                return SceneInitType::Push(Box::new(SceneInitType::Pause));
+            }
+        }
+
+        if self.resign_button.update(rl) {
+            let outcome = match self.player_turn {
+                Turn::White => Outcome::BlackWins,
+                Turn::Black => Outcome::WhiteWins,
+            };
+            self.finish(rl, thread, GameResult::new(outcome, EndReason::Resignation));
+            return SceneInitType::None;
+        }
+
+        if self.draw_button.update(rl) {
+            self.finish(rl, thread, GameResult::new(Outcome::Draw, EndReason::Agreement));
+            return SceneInitType::None;
+        }
+
+        // a claim is made by the player to move, so it is only offered when both sit here. The
+        // protocol has no message to claim a draw with, remote games are drawn by the server at
+        // fivefold repetition or the seventy-five-move rule instead
+        let claim = self.draw_rules.claimable().filter(|_| self.is_local());
+        self.claim_button.set_enabled(claim.is_some());
+        if let Some(reason) = claim {
+            if self.claim_button.update(rl) {
+                self.finish(rl, thread, GameResult::new(Outcome::Draw, reason));
+                return SceneInitType::None;
+            }
+        }
+
+        // checkmate on the move that reaches the limit still wins, and a promotion finishes the move first
+        if !matches!(self.chess.get_game_state(), GameState::CheckMate | GameState::Promotion(..)) {
+            if let Some(reason) = self.draw_rules.forced() {
+                self.finish(rl, thread, GameResult::new(Outcome::Draw, reason));
+                return SceneInitType::None;
+            }
+        }
+
+        if self.save_pgn_button.update(rl) {
+            self.save(self.to_pgn("*"), "pgn");
+        }
+
+        if self.save_fen_button.update(rl) {
+            self.save(self.to_fen(), "fen");
+        }
+
+        if self.edit_button.update(rl) {
+            return SceneInitType::Editor(Some(self.to_fen()));
+        }
+
+        if self.move_input.update(rl) {
+            self.submit_typed_move();
+        }
+
         match self.chess.get_game_state() {
             GameState::Ongoing => self.on_ongoing(rl),
             GameState::Promotion(..) => {
-                if self.get_player_mut().on_promotion() {
-                    self.update_promotion(rl);
+                // the piece is known already when it was typed, picked before sending or received
+                match self.typed_promotion.take().or_else(|| self.get_player_mut().take_promotion()) {
+                    Some(piece) => {
+                        self.promote(piece);
+                    },
+                    None => if self.get_player_mut().on_promotion() {
+                        self.update_promotion(rl);
+                    },
                 }
             },
-            GameState::CheckMate | GameState::Draw => self.get_player_mut().on_end(),
+            GameState::CheckMate => {
+                self.get_player_mut().on_end();
+                // the player to move is the one who got mated
+                let outcome = match self.chess.get_player_turn() {
+                    Turn::White => Outcome::BlackWins,
+                    Turn::Black => Outcome::WhiteWins,
+                };
+                self.finish(rl, thread, GameResult::new(outcome, EndReason::CheckMate));
+            },
+            GameState::Draw => {
+                self.get_player_mut().on_end();
+                // the engine also draws positions the side to move can still play on from, only
+                // having no legal move without being in check is a stalemate
+                let reason = if has_legal_move(&self.chess) { EndReason::DrawnPosition } else { EndReason::Stalemate };
+                self.finish(rl, thread, GameResult::new(Outcome::Draw, reason));
+            },
             _ => {},
         }
 
@@ -62,49 +241,322 @@ impl Scene for Game {
 }
 
 impl Game {
-    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, players: [PlayerTypes; 2]) -> Self {
-        Game {
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, settings: &SharedSettings, players: [PlayerTypes; 2]) -> Self {
+        Self::init_from(rl, thread, resources, settings, players, GameSetup::default())
+    }
+
+    /// Start a game from the position and moves in `setup`. A position that can't be set up or a
+    /// move that can't be replayed is reported as a message on the board
+    pub fn init_from(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, shared_settings: &SharedSettings, players: [PlayerTypes; 2], setup: GameSetup) -> Self {
+        let settings = shared_settings.get();
+
+        // resigning and agreeing to a draw is only supported when both players sit at this computer
+        let is_local = players.iter().all(|player| matches!(player, PlayerTypes::Local(_)));
+
+        let mut resign_button = create_side_button(rl, thread, resources, "Resign");
+        resign_button.set_enabled(is_local);
+        let mut draw_button = create_side_button(rl, thread, resources, "Draw");
+        draw_button.set_enabled(is_local);
+        let mut claim_button = create_side_button(rl, thread, resources, "Claim draw");
+        claim_button.set_enabled(false);
+        // leaving for the editor would abandon a remote game
+        let mut edit_button = create_side_button(rl, thread, resources, "Edit");
+        edit_button.set_enabled(is_local);
+
+        let themes = resources.themes();
+        let theme = selected_theme_index(&themes, shared_settings);
+
+        let mut game = Game {
             chess: ChessGame::default(),
-            white_textures: PIECE_NAMES.map(|name| load_piece_texture(rl, thread, "white", name)),
-            black_textures: PIECE_NAMES.map(|name| load_piece_texture(rl, thread, "black", name)),
-            // [white, black, selected, attackable]
-            colors: [ RayColor::get_color(WHITE_SLOT_COLOR), RayColor::get_color(BLACK_SLOT_COLOR), RayColor::get_color(SELECTED_SLOT_COLOR), RayColor::get_color(ATTACKABLE_SLOT_COLOR), RayColor::get_color(TURN_VISUAL_COLOR), RayColor::get_color(KING_SQUARE_IN_CHECK)],
+            start_fen: START_FEN.to_string(),
+            resources: resources.clone(),
+            settings: shared_settings.clone(),
+            pieces: resources.piece_set(rl, thread, &themes[theme].piece_set),
+            // [white, black, selected, attackable, turn bar, king in check, premove]
+            colors: themes[theme].ray_colors(),
+            themes,
+            theme,
             players,
             player_turn: Turn::White,
             promoted_slot: None,
             king_index: (4, 7 * 8 + 4),
-            animation: Animation::new(Animations::EaseInOutCirc, (0.2 * FPS as f32) as u32),
+            animation: Tween::new(0.0, 1.0, settings.animation_speed.seconds()).easing(Easing::InOutCirc),
+            moving: None,
+            move_animation: Tween::new(0.0, 1.0, settings.animation_speed.seconds()).easing(Easing::OutCubic),
+            captured: [vec![], vec![]],
+            history: vec![],
+            draw_rules: DrawRules::default(),
+            annotations: vec![],
+            annotation_start: None,
+            screen: Screen::of(rl),
+            flipped: settings.orientation == Orientation::BlackAtBottom,
+            show_coordinates: settings.show_coordinates,
+            confirm_moves: settings.confirm_moves,
+            pending_move: None,
+            resign_button,
+            draw_button,
+            claim_button,
+            save_pgn_button: create_side_button(rl, thread, resources, "Save PGN"),
+            save_fen_button: create_side_button(rl, thread, resources, "Save FEN"),
+            edit_button,
+            move_input: create_move_input(rl, thread, resources),
+            typed_promotion: None,
+            promotion_move: None,
+            message: None,
+            message_font: resources.ui_font(rl, thread, MESSAGE_FONT_SIZE),
+            coordinate_font: resources.ui_font(rl, thread, components::COORDINATE_FONT_SIZE),
+            capture_font: resources.ui_font(rl, thread, captures::CAPTURE_FONT_SIZE),
+            end: None,
+        };
+
+        game.layout(rl);
+        game.set_position(setup.fen.as_deref(), &setup.moves);
+        game
+    }
+
+    /// Reset the board to the position of `fen`, the standard position if there is none, and
+    /// replay `moves` on top of it. A position that can't be set up or a move that can't be
+    /// replayed is reported as a message on the board
+    pub fn set_position(&mut self, fen: Option<&str>, moves: &[String]) {
+        self.message = None;
+        self.start_fen = fen.unwrap_or(START_FEN).to_string();
+        self.chess = match chess_from_fen(&self.start_fen) {
+            Ok(chess) => chess,
+            Err(e) => {
+                self.message = Some(e);
+                self.start_fen = START_FEN.to_string();
+                ChessGame::default()
+            }
+        };
+
+        self.player_turn = match self.chess.get_player_turn() {
+            Turn::White => Turn::White,
+            Turn::Black => Turn::Black,
+        };
+
+        self.promoted_slot = None;
+        self.captured = [vec![], vec![]];
+        self.history.clear();
+        self.draw_rules = DrawRules::new(&self.start_fen);
+        self.clear_annotations();
+        self.pending_move = None;
+        self.typed_promotion = None;
+        self.promotion_move = None;
+        self.end = None;
+        self.moving = None;
+        for player in &mut self.players {
+            player.cancel_move();
+        }
+
+        self.king_index = (self.find_king(Turn::White), self.find_king(Turn::Black));
+
+        for san in moves {
+            if let Err(e) = self.play_san(san) {
+                self.message = Some(e);
+                break;
+            }
+        }
+    }
+
+    /// Everything on and around the board, without the controls of a game being played
+    pub fn draw_board(&mut self, draw_handler: &mut RaylibDrawHandle) {
+        // scenes pushed over the game stop its updates, but the window may still be resized
+        self.screen = Screen::of(draw_handler);
+
+        self.draw_board_background(draw_handler);
+        self.draw_coordinates(draw_handler);
+        self.draw_special_state(draw_handler);
+        self.draw_attackable_slots(draw_handler);
+        self.draw_premoves(draw_handler);
+        self.draw_annotations(draw_handler);
+        self.draw_pieces_on_board(draw_handler);
+        self.draw_player_turn_bar(draw_handler);
+        self.draw_captured_pieces(draw_handler);
+    }
+
+    /// Move the board animations `seconds` forward, for scenes that show the board without
+    /// updating the game
+    pub fn advance_animations(&mut self, seconds: f32) {
+        self.animation.advance(seconds);
+        self.move_animation.advance(seconds);
+        if self.move_animation.is_finished() {
+            self.moving = None;
         }
     }
 
+    pub fn draw_message(&self, draw_handler: &mut RaylibDrawHandle) {
+        if let Some(message) = &self.message {
+            let (width, _) = measure(&self.message_font, message, MESSAGE_FONT_SIZE);
+            draw_text(draw_handler, &self.message_font, message, (self.screen.width() - width) / 2, MESSAGE_TOP_PADDING, MESSAGE_FONT_SIZE, RayColor::WHITE);
+        }
+    }
+
+    /// The moves played so far in algebraic notation
+    pub fn move_list(&self) -> Vec<String> {
+        self.history.iter().map(|record| record.san.clone()).collect()
+    }
+
+    pub fn get_start_fen(&self) -> &str {
+        &self.start_fen
+    }
+
+    /// Switch to the theme at `index` in the loaded themes, along with its piece set
+    fn set_theme(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread, index: usize) {
+        self.theme = index;
+
+        let theme = &self.themes[index];
+        self.colors = theme.ray_colors();
+        self.pieces = self.resources.piece_set(rl, thread, &theme.piece_set);
+
+        select_theme(&self.settings, &self.themes[index].name);
+    }
+
+    /// Pick up the themes and pieces that were reloaded after changing on disk
+    fn refresh_resources(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) {
+        if self.themes.is_stale() {
+            self.themes = self.resources.themes();
+            self.set_theme(rl, thread, selected_theme_index(&self.themes, &self.settings));
+        }
+        if self.pieces.is_stale() {
+            self.pieces = self.resources.piece_set(rl, thread, &self.themes[self.theme].piece_set);
+        }
+    }
+
+    /// Place the buttons and the move input around the board, which is sized to the window
+    fn layout(&mut self, rl: &RaylibHandle) {
+        self.screen = Screen::of(rl);
+
+        let screen = &self.screen;
+        for (index, button) in [&mut self.resign_button, &mut self.draw_button, &mut self.claim_button, &mut self.save_pgn_button, &mut self.save_fen_button, &mut self.edit_button].into_iter().enumerate() {
+            button.set_bounds(SIDE_BUTTON_LEFT, side_button_top(screen, index as i32), SIDE_BUTTON_WIDTH, SIDE_BUTTON_HEIGHT);
+        }
+
+        self.move_input.set_bounds((screen.width() - MOVE_INPUT_WIDTH) / 2, move_input_top(screen), MOVE_INPUT_WIDTH, MOVE_INPUT_HEIGHT);
+    }
+
+    fn save(&mut self, contents: String, extension: &str) {
+        self.message = Some(match save_to_file(&contents, extension) {
+            Ok(path) => format!("Saved to {}", path.display()),
+            Err(e) => format!("Could not save game: {}", e),
+        });
+    }
+
+    fn submit_typed_move(&mut self) {
+        if !matches!(self.chess.get_game_state(), GameState::Ongoing) {
+            self.message = Some("Moves can't be entered right now".to_string());
+            return;
+        }
+
+        let text = self.move_input.get_text().clone();
+        match self.find_move(&text) {
+            Ok((from, to, promotion)) => {
+                // goes through the player like a mouse move so that remote games send it to the server
+                if self.get_player_mut().queue_move(from, to) {
+                    self.typed_promotion = promotion;
+                    self.move_input.clear();
+                    self.message = None;
+                } else {
+                    self.typed_promotion = None;
+                    self.message = Some("It is not your turn".to_string());
+                }
+            },
+            Err(e) => {
+                self.typed_promotion = None;
+                self.message = Some(e);
+            },
+        }
+    }
+
+    fn find_king(&self, side: Turn) -> i32 {
+        (0..64)
+            .find(|&index| matches!(self.get_piece(index), Some((other, 5)) if same_side(&other, &side)))
+            .unwrap_or(-1)
+    }
+
+    fn finish(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread, result: GameResult) {
+        let pgn = self.to_pgn(result.score());
+        let rematch = match self.players.each_ref().map(|player| player.rematch()) {
+            [Some(white), Some(black)] => Some(([white, black], GameSetup { fen: Some(self.start_fen.clone()), moves: vec![] })),
+            _ => None,
+        };
+        self.end = Some(End::init(rl, thread, &self.resources, result, pgn, rematch));
+    }
+
+    fn is_local(&self) -> bool {
+        self.players.iter().all(|player| matches!(player, PlayerTypes::Local(_)))
+    }
+
     fn on_ongoing(&mut self, rl: &mut RaylibHandle) {
-        self.get_player_mut().on_ongoing(rl);
+        if let Some((from, to)) = self.pending_move {
+            self.update_pending_move(rl, from, to);
+            return;
+        }
+
+        if self.promotion_move.is_some() {
+            self.update_promotion_move(rl);
+            return;
+        }
+
+        let hovered = self.hovered_slot(rl);
+        self.get_waiting_player_mut().on_waiting(rl, hovered);
+
+        // play the first premove that is still legal now that the opponent has moved
+        while let Some((from, to)) = self.get_player_mut().take_premove() {
+            let to = self.castling_move(from, to).unwrap_or(to);
+            if self.chess.get_valid_moves(&index_to_position(from)).get(&index_to_position(to)).is_some() {
+                self.get_player_mut().queue_move(from, to);
+                break;
+            }
+        }
+
+        let was_dragging = self.get_player().is_dragging();
+        self.get_player_mut().on_ongoing(rl, hovered);
 
         if let Some(selected) = self.get_player().get_selected_slot() {
-            let (sx, sy) = (selected as u8 % 8, selected as u8 / 8);
-            let from = BoardPosition::try_from((sx, sy)).unwrap();
+            let from = index_to_position(selected);
 
             if let Some(move_to) = self.get_player().get_move() {
-                let to = BoardPosition::try_from((move_to as u8 % 8, move_to as u8 / 8)).unwrap();
+                // a king put onto its own rook castles instead of picking the rook
+                let move_to = match self.castling_move(selected, move_to) {
+                    Some(target) if target != move_to => {
+                        self.get_player_mut().queue_move(selected, target);
+                        target
+                    },
+                    _ => move_to,
+                };
+                let to = index_to_position(move_to);
 
-                if !self.get_player_mut().on_move_piece() {
+                // illegal target, snap the piece back without bothering the player (or server)
+                if self.chess.get_valid_moves(&from).get(&to).is_none() {
+                    self.get_player_mut().cancel_move();
+                    self.typed_promotion = None;
                     return;
                 }
 
-                let moved =  self.chess.move_piece(&from, &to);
+                if self.typed_promotion.is_none() && self.promotes(selected, move_to) && matches!(self.get_player(), PlayerTypes::RemoteSend(_)) {
+                    let (sx, sy) = (selected % 8, selected / 8);
+                    self.promoted_slot = Some((sx + (sy & 1) + 1) & 1);
+                    self.promotion_move = Some((selected, move_to));
+                    return;
+                }
 
-                if moved.is_ok() {
-                    self.player_turn = match self.chess.get_player_turn() {
-                        Turn::White => Turn::White,
-                        Turn::Black => Turn::Black,
-                    };
+                // moves from the remote opponent are never held back
+                if self.confirm_moves && !matches!(self.get_player(), PlayerTypes::RemoteRecv(_)) {
+                    self.pending_move = Some((selected, move_to));
+                    self.message = Some("Enter to confirm the move, Escape to take it back".to_string());
+                    return;
+                }
 
-                    self.animation.restart();
-                    self.update_king_index(selected, move_to);
+                let promotion = self.typed_promotion;
+                if !self.get_player_mut().on_move_piece(promotion) {
+                    self.typed_promotion = None;
+                    return;
+                }
 
-                    if let GameState::Promotion(..) = moved.unwrap() {
-                        self.promoted_slot = Some(((sx + (sy & 1) + 1) & 1) as i32)
-                    }
+                self.apply_move(selected, move_to);
+                // a piece dropped by the mouse is already on its square
+                if was_dragging {
+                    self.moving = None;
                 }
             } else if let Some(piece) = self.chess.get_square(&from) {
                 let turn = self.chess.get_player_turn();
@@ -118,6 +570,81 @@ impl Game {
         }
     }
 
+    fn update_pending_move(&mut self, rl: &mut RaylibHandle, from: i32, to: i32) {
+        if rl.is_key_pressed(KeyboardKey::KEY_ENTER) && !self.move_input.is_selected() {
+            self.pending_move = None;
+            self.message = None;
+            let promotion = self.typed_promotion;
+            if self.get_player_mut().on_move_piece(promotion) {
+                self.apply_move(from, to);
+            } else {
+                self.typed_promotion = None;
+            }
+        } else if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) || rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT) {
+            self.pending_move = None;
+            self.message = None;
+            self.typed_promotion = None;
+            self.get_player_mut().cancel_move();
+        }
+    }
+
+    /// Play a move on the board and keep track of captures and history
+    ///
+    /// Return if the move was legal
+    pub fn apply_move(&mut self, from: i32, to: i32) -> bool {
+        let mut captured = self.get_captured_piece(from, to);
+        let mut san = self.move_to_san(from, to);
+        let castling_rooks = self.castling_rooks(from);
+
+        let Ok(state) = self.chess.move_piece(&index_to_position(from), &index_to_position(to)) else {
+            return false;
+        };
+
+        // in Chess960 the king can castle onto its rook or by a single file, which only the board
+        // after the move tells apart from other king moves
+        let mut king_to = to;
+        if let Some(rook) = self.castled_with(from, to, &castling_rooks) {
+            captured = None;
+            san = if rook > from { "O-O".to_string() } else { "O-O-O".to_string() };
+            king_to = from / 8 * 8 + if rook > from { 6 } else { 2 };
+        }
+
+        if let Some(piece) = captured {
+            self.record_capture(piece);
+        }
+        self.history.push(MoveRecord { from, to, promotion: None, san });
+        self.clear_annotations();
+
+        self.player_turn = match self.chess.get_player_turn() {
+            Turn::White => Turn::White,
+            Turn::Black => Turn::Black,
+        };
+
+        self.animation.restart();
+        self.moving = Some((from, king_to));
+        self.move_animation.restart();
+        self.update_king_index(from, king_to);
+
+        if let GameState::Promotion(..) = state {
+            let (sx, sy) = (from % 8, from / 8);
+            self.promoted_slot = Some((sx + (sy & 1) + 1) & 1)
+        } else {
+            let suffix = self.check_suffix();
+            if let Some(record) = self.history.last_mut() {
+                record.san += suffix;
+            }
+            self.record_position();
+        }
+
+        true
+    }
+
+    /// Count the position on the board for the draw rules, once the move is finished
+    pub fn record_position(&mut self) {
+        let fen = self.to_fen();
+        self.draw_rules.record(&fen);
+    }
+
     fn update_king_index(&mut self, from: i32, to: i32) {
         // inverse since the turn has shifted to the opponent player since the move was made
         match self.chess.get_player_turn() {
@@ -134,7 +661,7 @@ impl Game {
         }
     }
 
-    pub fn get_player(&self) -> &impl Player {
+    pub fn get_player(&self) -> &PlayerTypes {
         match self.player_turn {
             Turn::White => &self.players[0],
             Turn::Black => &self.players[1],
@@ -148,6 +675,26 @@ impl Game {
         }
     }
 
+    /// Draw black at the bottom of the board
+    pub fn set_flipped(&mut self, value: bool) {
+        self.flipped = value;
+    }
+
+    /// The player who is not to move
+    pub fn get_waiting_player(&self) -> &impl Player {
+        match self.player_turn {
+            Turn::White => &self.players[1],
+            Turn::Black => &self.players[0],
+        }
+    }
+
+    pub fn get_waiting_player_mut(&mut self) -> &mut impl Player {
+        match self.player_turn {
+            Turn::White => &mut self.players[1],
+            Turn::Black => &mut self.players[0],
+        }
+    }
+
     pub fn get_game_state(&self) -> &GameState {
         self.chess.get_game_state()
     }
@@ -155,4 +702,49 @@ impl Game {
     pub fn get_player_turn(&self) -> &Turn {
         self.chess.get_player_turn()
     }
+
+    /// Board slot under the mouse, following the orientation of the board
+    pub fn hovered_slot(&self, rl: &RaylibHandle) -> Option<i32> {
+        let (x, y) = self.screen.screen_to_board_coord(rl.get_mouse_x(), rl.get_mouse_y(), self.flipped);
+
+        if !(0..8).contains(&x) || !(0..8).contains(&y) {
+            return None;
+        }
+
+        Some(y * 8 + x)
+    }
+
+    /// Color and index into PIECE_NAMES of the piece on the given slot
+    pub fn get_piece(&self, index: i32) -> Option<(Turn, usize)> {
+        piece_at(&self.chess, index).map(|(white, piece)| (if white { Turn::White } else { Turn::Black }, piece))
+    }
+}
+
+fn same_side(a: &Turn, b: &Turn) -> bool {
+    matches!((a, b), (Turn::White, Turn::White) | (Turn::Black, Turn::Black))
+}
+
+fn create_move_input(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources) -> Input {
+    let mut input = Input::init(resources.ui_font(rl, thread, MOVE_INPUT_FONT_SIZE), MOVE_INPUT_FONT_SIZE);
+    input.set_placeholder("Type a move");
+    input.set_bg_color(RayColor::get_color(0xff_ff_ff_0a));
+    input.set_selected_bg_color(RayColor::get_color(0xff_ff_ff_1a));
+    input.set_border_color(RayColor::get_color(0xff_ff_ff_a0));
+    input
+}
+
+fn create_side_button(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, text: &'static str) -> Button {
+    let mut button = Button::new(resources.ui_font(rl, thread, SIDE_BUTTON_FONT_SIZE));
+    button.set_text(text, SIDE_BUTTON_FONT_SIZE);
+    button
+}
+
+/// The side buttons are stacked next to the middle of the board
+fn side_button_top(screen: &Screen, index: i32) -> i32 {
+    let total_height = SIDE_BUTTON_COUNT * SIDE_BUTTON_HEIGHT + (SIDE_BUTTON_COUNT - 1) * SIDE_BUTTON_SPACING;
+    screen.board_offset_y() + (screen.board_size() - total_height) / 2 + index * (SIDE_BUTTON_HEIGHT + SIDE_BUTTON_SPACING)
+}
+
+fn move_input_top(screen: &Screen) -> i32 {
+    screen.board_offset_y() + screen.board_size() + MOVE_INPUT_OFFSET
 }
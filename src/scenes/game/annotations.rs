@@ -0,0 +1,118 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use super::player::Player;
+use super::Game;
+
+// [default, shift, ctrl, alt]
+const ANNOTATION_COLORS : [u32 ; 4] = [0x15_78_1b_b0, 0xd4_2a_2a_b0, 0x2a_64_d4_b0, 0xe6_b3_20_b0];
+
+// Arrow dimensions as a fraction of the tile size
+const ARROW_WIDTH : f32 = 0.2;
+const ARROW_HEAD_LENGTH : f32 = 0.4;
+const ARROW_HEAD_WIDTH : f32 = 0.47;
+const ARROW_START_OFFSET : f32 = 0.2;
+const ARROW_END_OFFSET : f32 = 0.13;
+const SQUARE_RING_WIDTH : f32 = 4.0;
+
+/// Arrow between two slots drawn with a right-click drag, or a highlighted slot when `from` and
+/// `to` are the same
+pub struct Annotation {
+    from: i32,
+    to: i32,
+    color: usize,
+}
+
+impl Game {
+    pub fn update_annotations(&mut self, rl: &mut RaylibHandle) {
+        // a right-click while a piece is dragged only snaps it back, which the player takes care of
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT) && !self.players.iter().any(|player| player.is_dragging()) {
+            self.annotation_start = self.hovered_slot(rl);
+        }
+
+        if !rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_RIGHT) {
+            return;
+        }
+
+        let (from, to) = (self.annotation_start.take(), self.hovered_slot(rl));
+
+        // a click that didn't become an arrow drops the selection and the queued premoves
+        if from.is_none() || from == to {
+            for player in &mut self.players {
+                player.on_right_click();
+            }
+        }
+
+        let (Some(from), Some(to)) = (from, to) else {
+            return;
+        };
+
+        let color = if rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT) {
+            1
+        } else if rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL) || rl.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL) {
+            2
+        } else if rl.is_key_down(KeyboardKey::KEY_LEFT_ALT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_ALT) {
+            3
+        } else {
+            0
+        };
+
+        // drawing the same annotation again removes it, or recolors it if the color differs
+        match self.annotations.iter().position(|annotation| annotation.from == from && annotation.to == to) {
+            Some(index) if self.annotations[index].color == color => {
+                self.annotations.remove(index);
+            },
+            Some(index) => self.annotations[index].color = color,
+            None => self.annotations.push(Annotation { from, to, color }),
+        }
+    }
+
+    pub fn clear_annotations(&mut self) {
+        self.annotations.clear();
+        self.annotation_start = None;
+    }
+
+    pub fn draw_annotations(&self, draw_handler: &mut RaylibDrawHandle) {
+        for annotation in &self.annotations {
+            let color = RayColor::get_color(ANNOTATION_COLORS[annotation.color]);
+
+            if annotation.from == annotation.to {
+                let (px, py) = self.screen.board_coord_to_screen(annotation.from % 8, annotation.from / 8, self.flipped);
+                let rect = Rectangle::new(px as f32, py as f32, self.screen.tile_size() as f32, self.screen.tile_size() as f32);
+                draw_handler.draw_rectangle_lines_ex(rect, SQUARE_RING_WIDTH, color);
+            } else {
+                draw_arrow(draw_handler, self.slot_center(annotation.from), self.slot_center(annotation.to), self.screen.tile_size() as f32, color);
+            }
+        }
+    }
+
+    fn slot_center(&self, index: i32) -> Vector2 {
+        let (px, py) = self.screen.board_coord_to_screen(index % 8, index / 8, self.flipped);
+        Vector2 { x: (px + self.screen.tile_size() / 2) as f32, y: (py + self.screen.tile_size() / 2) as f32 }
+    }
+}
+
+fn draw_arrow(draw_handler: &mut RaylibDrawHandle, start: Vector2, end: Vector2, tile_size: f32, color: RayColor) {
+    let length = ((end.x - start.x).powi(2) + (end.y - start.y).powi(2)).sqrt();
+    let direction = Vector2 { x: (end.x - start.x) / length, y: (end.y - start.y) / length };
+    let normal = Vector2 { x: -direction.y, y: direction.x };
+
+    let (end_offset, head_length, head_width, start_offset) = (ARROW_END_OFFSET * tile_size, ARROW_HEAD_LENGTH * tile_size, ARROW_HEAD_WIDTH * tile_size, ARROW_START_OFFSET * tile_size);
+
+    let tip = Vector2 { x: end.x - direction.x * end_offset, y: end.y - direction.y * end_offset };
+    let base = Vector2 { x: tip.x - direction.x * head_length, y: tip.y - direction.y * head_length };
+    let line_start = Vector2 { x: start.x + direction.x * start_offset, y: start.y + direction.y * start_offset };
+
+    draw_handler.draw_line_ex(line_start, base, ARROW_WIDTH * tile_size, color);
+
+    let left = Vector2 { x: base.x + normal.x * head_width / 2.0, y: base.y + normal.y * head_width / 2.0 };
+    let right = Vector2 { x: base.x - normal.x * head_width / 2.0, y: base.y - normal.y * head_width / 2.0 };
+
+    // raylib only fills triangles given in counter-clockwise order
+    let cross = (left.x - tip.x) * (right.y - tip.y) - (left.y - tip.y) * (right.x - tip.x);
+    if cross < 0.0 {
+        draw_handler.draw_triangle(tip, left, right, color);
+    } else {
+        draw_handler.draw_triangle(tip, right, left, color);
+    }
+}
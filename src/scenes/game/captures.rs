@@ -0,0 +1,89 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use viktoe_chess::board::Turn;
+use viktoe_chess::piece::{Color, Piece};
+
+use crate::chess::{index_to_position, piece_index, PIECE_COUNT};
+use crate::ui::draw_text;
+
+use super::textures::draw_piece;
+use super::Game;
+
+// Indexed the same way as PIECE_NAMES
+const PIECE_VALUES : [i32 ; PIECE_COUNT] = [1, 3, 3, 5, 9, 0];
+
+const CAPTURE_ICON_SIZE : i32 = 30;
+const CAPTURE_ICON_STEP : i32 = 18;
+const CAPTURE_TRAY_OFFSET : i32 = 30;
+pub(super) const CAPTURE_FONT_SIZE : i32 = 20;
+const CAPTURE_TEXT_PADDING : i32 = 8;
+
+impl Game {
+    /// Find which piece, if any, is taken by moving from `from` to `to`. Has to be called before the
+    /// move is played on the board
+    pub fn get_captured_piece(&self, from: i32, to: i32) -> Option<usize> {
+        if let Some(Color::White(piece) | Color::Black(piece)) = self.chess.get_square(&index_to_position(to)) {
+            return Some(piece_index(piece));
+        }
+
+        // a pawn moving diagonally onto an empty square can only be en passant
+        match self.chess.get_square(&index_to_position(from)) {
+            Some(Color::White(piece @ Piece::Pawn { .. }) | Color::Black(piece @ Piece::Pawn { .. })) if from % 8 != to % 8 => Some(piece_index(piece)),
+            _ => None,
+        }
+    }
+
+    /// Store a piece taken by the player whose turn it is, before the turn is handed over
+    pub fn record_capture(&mut self, piece: usize) {
+        let captured = match self.player_turn {
+            Turn::White => &mut self.captured[0],
+            Turn::Black => &mut self.captured[1],
+        };
+
+        captured.push(piece);
+        captured.sort_by_key(|&index| (PIECE_VALUES[index], index));
+    }
+
+    /// Material of white minus material of black, counted from the board so that promotions are
+    /// included
+    pub fn material_balance(&self) -> i32 {
+        let mut balance = 0;
+        for index in 0..64 {
+            match self.chess.get_square(&index_to_position(index)) {
+                Some(Color::White(piece)) => balance += PIECE_VALUES[piece_index(piece)],
+                Some(Color::Black(piece)) => balance -= PIECE_VALUES[piece_index(piece)],
+                None => {}
+            }
+        }
+        balance
+    }
+
+    pub fn draw_captured_pieces(&self, draw_handler: &mut RaylibDrawHandle) {
+        let balance = self.material_balance();
+
+        // each side shows the pieces it has taken next to its edge of the board
+        let mut bottom_y = self.screen.board_offset_y() + self.screen.board_size() + CAPTURE_TRAY_OFFSET;
+        let mut top_y = self.screen.board_offset_y() - CAPTURE_TRAY_OFFSET - CAPTURE_ICON_SIZE;
+        if self.flipped {
+            (bottom_y, top_y) = (top_y, bottom_y);
+        }
+
+        self.draw_capture_tray(draw_handler, &self.captured[0], &self.pieces.black, bottom_y, balance);
+        self.draw_capture_tray(draw_handler, &self.captured[1], &self.pieces.white, top_y, -balance);
+    }
+
+    fn draw_capture_tray(&self, draw_handler: &mut RaylibDrawHandle, captured: &[usize], textures: &[Texture2D ; PIECE_COUNT], y: i32, advantage: i32) {
+        let mut x = self.screen.board_offset_x();
+
+        for &index in captured {
+            draw_piece(draw_handler, &textures[index], x, y, CAPTURE_ICON_SIZE);
+            x += CAPTURE_ICON_STEP;
+        }
+
+        if advantage > 0 {
+            let text_y = y + (CAPTURE_ICON_SIZE - CAPTURE_FONT_SIZE) / 2;
+            draw_text(draw_handler, &self.capture_font, &format!("+{}", advantage), x + CAPTURE_ICON_SIZE - CAPTURE_ICON_STEP + CAPTURE_TEXT_PADDING, text_y, CAPTURE_FONT_SIZE, RayColor::WHITE);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::chess::{chess960::castling_slots, fen::parse_fen, index_to_position};
+
+use super::Game;
+
+const ROOK : usize = 3;
+
+impl Game {
+    /// Rooks the king on `king` can still castle with
+    pub fn castling_rooks(&self, king: i32) -> Vec<i32> {
+        let Ok(start) = parse_fen(&self.start_fen) else {
+            return vec![];
+        };
+
+        let rights = self.castling_rights(&start.castling);
+        castling_slots(&start.placement).into_iter()
+            .filter(|&(right, other, _)| other == king && rights.contains(right))
+            .map(|(_, _, rook)| rook)
+            .collect()
+    }
+
+    /// Move to pass to the engine for the king on `from` being put onto its own rook on `to`,
+    /// which is how castling is entered when the king may already stand next to its destination
+    ///
+    /// The engine takes castling as the king moving to the g- or c-file, or onto the rook when the
+    /// king starts on that file. None if the king can't castle with that rook right now
+    pub fn castling_move(&self, from: i32, to: i32) -> Option<i32> {
+        if !self.castling_rooks(from).contains(&to) {
+            return None;
+        }
+
+        let destination = from / 8 * 8 + if to > from { 6 } else { 2 };
+        [destination, to].into_iter()
+            .find(|&target| target != from && self.chess.get_valid_moves(&index_to_position(from)).get(&index_to_position(target)).is_some())
+    }
+
+    /// Rook the king castled with, if the move of the king from `from` to `to` was castling.
+    /// `rooks` are the castling rooks from before the move, which has to be played already
+    pub fn castled_with(&self, from: i32, to: i32, rooks: &[i32]) -> Option<i32> {
+        castled_rook(from, to, rooks, |rook| matches!(self.get_piece(rook), Some((_, ROOK))))
+    }
+}
+
+/// Rook out of `rooks` the king moving from `from` to `to` castled with, `rook_in_place` telling
+/// whether a rook still stands on its square after the move
+fn castled_rook(from: i32, to: i32, rooks: &[i32], rook_in_place: impl Fn(i32) -> bool) -> Option<i32> {
+    // only a king staying on its back rank can have castled
+    if from / 8 != to / 8 {
+        return None;
+    }
+
+    rooks.iter().copied().find(|&rook| {
+        // the king went onto the rook or two files towards it, or the rook left its square
+        rook == to
+            || ((to % 8 - from % 8).abs() >= 2 && (rook > from) == (to > from))
+            || !rook_in_place(rook)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_rook_castled_with() {
+        let rooks = [0, 7];
+        assert_eq!(castled_rook(4, 6, &rooks, |_| true), Some(7));
+        assert_eq!(castled_rook(4, 2, &rooks, |_| true), Some(0));
+        assert_eq!(castled_rook(4, 7, &rooks, |_| true), Some(7));
+        // a chess960 king next to its destination castles by taking its rook off its square
+        assert_eq!(castled_rook(1, 2, &[0], |rook| rook != 0), Some(0));
+    }
+
+    #[test]
+    fn other_king_moves_are_not_castling() {
+        let rooks = [0, 7];
+        assert_eq!(castled_rook(4, 5, &rooks, |_| true), None);
+        assert_eq!(castled_rook(4, 12, &rooks, |_| true), None);
+        assert_eq!(castled_rook(4, 13, &rooks, |_| true), None);
+        assert_eq!(castled_rook(4, 11, &rooks, |_| true), None);
+        assert_eq!(castled_rook(60, 52, &[56, 63], |_| true), None);
+    }
+}
@@ -3,65 +3,104 @@ use raylib::color::{Color as RayColor};
 use viktoe_chess::board::{GameState, Turn};
 use viktoe_chess::prelude::BoardPosition;
 
-use crate::scenes::game::screen::BOARD_OFFSET_X;
-
 use super::player::Player;
-use super::{SceneStorage, Game, screen, promotion};
+use super::{SceneStorage, Game, promotion};
 
-use super::screen::{board_coord_to_screen, ATTACK_CIRCLE_RADIUS, BOARD_OFFSET_Y, BOARD_SIZE, IMAGE_SIZE, TILE_SIZE};
+use super::textures::draw_piece;
+use crate::ui::{animate::Lerp, draw_text, measure};
 
 const ATTACK_RING_WIDTH  : f32 = 5.0;
 const ATTACK_RING_PADDING : f32 = 3.0;
-const ATTACK_INNER_RADIUS : f32 = (TILE_SIZE as f32 / 2.0) - ATTACK_RING_WIDTH - ATTACK_RING_PADDING;
-const ATTACK_OUTER_RADIUS : f32 = (TILE_SIZE as f32 / 2.0) - ATTACK_RING_PADDING;
 
 const TURN_VISUAL_OFFSET : i32 = 15;
 const TURN_VISUAL_HEIGHT : i32 = 5;
-const TURN_VISUAL_WIDTH : i32 = BOARD_SIZE - 20;
+const TURN_VISUAL_INSET : i32 = 20;
+
+pub(super) const COORDINATE_FONT_SIZE : i32 = 14;
+const COORDINATE_PADDING : i32 = 3;
 
 impl Game {
     pub fn draw_board_background(&self, draw_handler: &mut RaylibDrawHandle) {
+        let tile_size = self.screen.tile_size();
         let mut color_index = 0;
         for y in 0..8 {
             for x in 0..8 {
-                let (px, py) = screen::board_coord_to_screen(x, y);
-                draw_handler.draw_rectangle(px, py, TILE_SIZE, TILE_SIZE, self.colors[color_index]);
+                let (px, py) = self.screen.board_coord_to_screen(x, y, self.flipped);
+                draw_handler.draw_rectangle(px, py, tile_size, tile_size, self.colors[color_index]);
                 color_index = (color_index + 1) & 1;
             }
             color_index = (color_index + 1) & 1;
         }
         
         if let Some(index) = self.get_player().get_selected_slot() {
-            let (px, py) = screen::board_coord_to_screen(index % 8, index / 8);
-            draw_handler.draw_rectangle(px, py, TILE_SIZE, TILE_SIZE, self.colors[2]);
+            let (px, py) = self.screen.board_coord_to_screen(index % 8, index / 8, self.flipped);
+            draw_handler.draw_rectangle(px, py, tile_size, tile_size, self.colors[2]);
+        }
+    }
+
+    pub fn draw_coordinates(&self, draw_handler: &mut RaylibDrawHandle) {
+        if !self.show_coordinates {
+            return;
+        }
+
+        // the edges the labels go on, rank 1 and the a-file unless black is at the bottom
+        let edge = if self.flipped { 7 } else { 0 };
+        let tile_size = self.screen.tile_size();
+
+        for i in 0..8 {
+            let file = ((b'a' + i as u8) as char).to_string();
+            let (px, py) = self.screen.board_coord_to_screen(i, edge, self.flipped);
+            let (width, _) = measure(&self.coordinate_font, &file, COORDINATE_FONT_SIZE);
+            let color = self.colors[((i + edge + 1) & 1) as usize];
+            draw_text(draw_handler, &self.coordinate_font, &file, px + tile_size - width - COORDINATE_PADDING, py + tile_size - COORDINATE_FONT_SIZE - COORDINATE_PADDING, COORDINATE_FONT_SIZE, color);
+
+            let rank = (i + 1).to_string();
+            let (px, py) = self.screen.board_coord_to_screen(edge, i, self.flipped);
+            let color = self.colors[((i + edge + 1) & 1) as usize];
+            draw_text(draw_handler, &self.coordinate_font, &rank, px + COORDINATE_PADDING, py + COORDINATE_PADDING, COORDINATE_FONT_SIZE, color);
         }
     }
 
     pub fn draw_pieces_on_board(&self, draw_handler: &mut RaylibDrawHandle) {
+        // the waiting player can be dragging a premove
+        let dragged = self.get_player().get_selected_slot().filter(|_| self.get_player().is_dragging())
+            .or(self.get_waiting_player().get_selected_slot().filter(|_| self.get_waiting_player().is_dragging()));
+
+        let tile_size = self.screen.tile_size();
         for y in 0..8 {
             for x in 0..8 {
-                if let Some(skip) = self.get_player().get_selected_slot() {
-                    if y * 8 + x == skip {
-                        continue;
-                    }
+                // the piece that just moved is drawn on its way there
+                if dragged == Some(y * 8 + x) || self.moving.is_some_and(|(_, to)| to == y * 8 + x) {
+                    continue;
                 }
                 if let Some(texture) = self.get_texture_for_square(x as u8, y as u8) {
-                    let (px, py) = screen::board_coord_to_screen(x, y);
-                    draw_handler.draw_texture(texture, px, py, RayColor::WHITE);
+                    let (px, py) = self.screen.board_coord_to_screen(x, y, self.flipped);
+                    draw_piece(draw_handler, texture, px, py, tile_size);
                 }
             }
         }
 
-        if let Some(index) = self.get_player().get_selected_slot() {
+        if let Some((from, to)) = self.moving {
+            if let Some(texture) = self.get_texture_for_square((to % 8) as u8, (to / 8) as u8) {
+                let (fx, fy) = self.screen.board_coord_to_screen(from % 8, from / 8, self.flipped);
+                let (tx, ty) = self.screen.board_coord_to_screen(to % 8, to / 8, self.flipped);
+                let t = self.move_animation.value();
+                draw_piece(draw_handler, texture, fx.lerp(tx, t), fy.lerp(ty, t), tile_size);
+            }
+        }
+
+        if let Some(index) = dragged {
             if let Some(texture) = self.get_texture_for_square((index % 8) as u8, (index / 8) as u8) {
-                let mouse = self.get_player().get_mouse();
-                draw_handler.draw_texture(texture, mouse.0 as i32 - IMAGE_SIZE / 2, mouse.1 as i32 - IMAGE_SIZE / 2, RayColor::WHITE);
+                let mouse = if self.get_player().is_dragging() { self.get_player().get_mouse() } else { self.get_waiting_player().get_mouse() };
+                draw_piece(draw_handler, texture, mouse.0 - tile_size / 2, mouse.1 - tile_size / 2, tile_size);
             }
         }
     }
 
     pub fn draw_attackable_slots(&self, draw_handler: &mut RaylibDrawHandle) {
         if let Some(slot) = self.get_player().get_selected_slot() {
+            let tile_size = self.screen.tile_size();
+            let outer_radius = tile_size as f32 / 2.0 - ATTACK_RING_PADDING;
             let moves = self.chess.get_valid_moves(&BoardPosition::try_from((slot as u8 % 8, slot as u8 / 8)).unwrap());
 
             for y in 0..8u8 {
@@ -71,26 +110,52 @@ impl Game {
                         continue;
                     }
 
-                    let (px, py) = screen::board_coord_to_screen(x as i32, y as i32);
+                    let (px, py) = self.screen.board_coord_to_screen(x as i32, y as i32, self.flipped);
                     if self.chess.get_square(&pos).is_some() {
-                        let center = Vector2{ x: (px + TILE_SIZE / 2) as f32, y: (py + TILE_SIZE / 2) as f32 };
-                        draw_handler.draw_ring(center, ATTACK_INNER_RADIUS, ATTACK_OUTER_RADIUS, 0.0, 360.0, 1, self.colors[3]);
+                        let center = Vector2{ x: (px + tile_size / 2) as f32, y: (py + tile_size / 2) as f32 };
+                        draw_handler.draw_ring(center, outer_radius - ATTACK_RING_WIDTH, outer_radius, 0.0, 360.0, 1, self.colors[3]);
                     } else {
-                        draw_handler.draw_circle(px + (TILE_SIZE) / 2, py + (TILE_SIZE) / 2, ATTACK_CIRCLE_RADIUS as f32, self.colors[3]);
+                        draw_handler.draw_circle(px + (tile_size) / 2, py + (tile_size) / 2, self.screen.attack_circle_radius() as f32, self.colors[3]);
                     }
                 }
             }
+
+            // castling is also entered by putting the king onto the rook
+            for rook in self.castling_rooks(slot).into_iter().filter(|&rook| self.castling_move(slot, rook).is_some()) {
+                let (px, py) = self.screen.board_coord_to_screen(rook % 8, rook / 8, self.flipped);
+                let center = Vector2{ x: (px + tile_size / 2) as f32, y: (py + tile_size / 2) as f32 };
+                draw_handler.draw_ring(center, outer_radius - ATTACK_RING_WIDTH, outer_radius, 0.0, 360.0, 1, self.colors[3]);
+            }
+        }
+    }
+
+    pub fn draw_premoves(&self, draw_handler: &mut RaylibDrawHandle) {
+        let waiting = self.get_waiting_player();
+        let slots = waiting.get_premoves().iter().flat_map(|&(from, to)| [from, to]).chain(waiting.get_selected_slot());
+
+        for index in slots {
+            let (px, py) = self.screen.board_coord_to_screen(index % 8, index / 8, self.flipped);
+            draw_handler.draw_rectangle(px, py, self.screen.tile_size(), self.screen.tile_size(), self.colors[6]);
+        }
+
+        // the origin of a move waiting for confirmation stays selected, mark its destination too
+        if let Some((_, to)) = self.pending_move {
+            let (px, py) = self.screen.board_coord_to_screen(to % 8, to / 8, self.flipped);
+            draw_handler.draw_rectangle(px, py, self.screen.tile_size(), self.screen.tile_size(), self.colors[2]);
         }
     }
 
-    pub fn draw_player_turn_bar(&mut self, draw_handler: &mut RaylibDrawHandle) {
-        let py = match self.chess.get_player_turn() {
-            Turn::White => BOARD_OFFSET_Y + BOARD_SIZE + TURN_VISUAL_OFFSET,
-            Turn::Black => BOARD_OFFSET_Y - TURN_VISUAL_OFFSET - TURN_VISUAL_HEIGHT,
+    pub fn draw_player_turn_bar(&self, draw_handler: &mut RaylibDrawHandle) {
+        let bottom = self.screen.board_offset_y() + self.screen.board_size() + TURN_VISUAL_OFFSET;
+        let top = self.screen.board_offset_y() - TURN_VISUAL_OFFSET - TURN_VISUAL_HEIGHT;
+        let py = match (self.chess.get_player_turn(), self.flipped) {
+            (Turn::White, false) | (Turn::Black, true) => bottom,
+            (Turn::White, true) | (Turn::Black, false) => top,
         };
         
-        let bar_width = ((TURN_VISUAL_WIDTH as f32 * self.animation.next()).floor() as i32).abs();
-        let px = BOARD_OFFSET_X + (BOARD_SIZE - bar_width) / 2;
+        let full_width = self.screen.board_size() - TURN_VISUAL_INSET;
+        let bar_width = ((full_width as f32 * self.animation.value()).floor() as i32).abs();
+        let px = self.screen.board_offset_x() + (self.screen.board_size() - bar_width) / 2;
 
         draw_handler.draw_rectangle(px, py, bar_width, TURN_VISUAL_HEIGHT, self.colors[4]);
     }
@@ -99,6 +164,7 @@ impl Game {
         match self.chess.get_game_state() {
             GameState::Check => self.draw_king_in_check(draw_handler),
             GameState::Promotion(..) => self.draw_promotion(draw_handler),
+            _ if self.promotion_move.is_some() => self.draw_promotion(draw_handler),
             _ => {}
         }
     }
@@ -109,8 +175,8 @@ impl Game {
             Turn::Black => self.king_index.1,
         };
         
-        let (px, py) = board_coord_to_screen(index % 8, index / 8);
-        draw_handler.draw_rectangle(px, py, TILE_SIZE, TILE_SIZE, self.colors[5]);
+        let (px, py) = self.screen.board_coord_to_screen(index % 8, index / 8, self.flipped);
+        draw_handler.draw_rectangle(px, py, self.screen.tile_size(), self.screen.tile_size(), self.colors[5]);
     }
 
 }
@@ -0,0 +1,55 @@
+use crate::chess::{chess960::castling_slots, fen::{parse_fen, placement_to_fen}, notation::square_name};
+
+use super::{Game, Turn};
+
+impl Game {
+    /// The current position as FEN
+    pub fn to_fen(&self) -> String {
+        let start = parse_fen(&self.start_fen).unwrap();
+
+        let placement = placement_to_fen(|index| self.get_piece(index).map(|(side, piece)| (matches!(side, Turn::White), piece)));
+
+        let side = match self.chess.get_player_turn() {
+            Turn::White => "w",
+            Turn::Black => "b",
+        };
+
+        let en_passant = match self.history.last() {
+            // a pawn that just moved two squares can be taken on the square it skipped
+            Some(record) if (record.to - record.from).abs() == 16 && matches!(self.get_piece(record.to), Some((_, 0))) => square_name((record.from + record.to) / 2),
+            Some(_) => "-".to_string(),
+            None => start.en_passant.map_or("-".to_string(), square_name),
+        };
+
+        let mut halfmove = start.halfmove;
+        for record in &self.history {
+            // pawn moves are the only moves written starting with a lowercase letter
+            if record.san.starts_with(|c: char| c.is_ascii_lowercase()) || record.san.contains('x') {
+                halfmove = 0;
+            } else {
+                halfmove += 1;
+            }
+        }
+
+        let black_moves = if start.white_to_move { self.history.len() / 2 } else { (self.history.len() + 1) / 2 };
+        let fullmove = start.fullmove as usize + black_moves;
+
+        format!("{} {} {} {} {} {}", placement, side, self.castling_rights(&start.castling), en_passant, halfmove, fullmove)
+    }
+
+    /// Castling rights of the start position that are still held, the king or rook of a right
+    /// having moved or the rook having been captured loses it
+    pub(super) fn castling_rights(&self, start: &str) -> String {
+        let placement = self.start_fen.split_whitespace().next().unwrap_or_default();
+        let slots = castling_slots(placement);
+
+        let mut rights = start.replace('-', "");
+        rights.retain(|c| {
+            slots.iter().any(|&(right, king, rook)| {
+                right == c && !self.history.iter().any(|record| [king, rook].contains(&record.from) || [king, rook].contains(&record.to))
+            })
+        });
+
+        if rights.is_empty() { "-".to_string() } else { rights }
+    }
+}
@@ -0,0 +1,101 @@
+use viktoe_chess::board::{GameState, Turn};
+
+use crate::chess::notation::{self, square_name, PIECE_LETTERS};
+
+use super::Game;
+
+const PAWN : usize = 0;
+const KING : usize = 5;
+
+pub struct MoveRecord {
+    pub from: i32,
+    pub to: i32,
+    /// Index into PIECE_NAMES of the piece a pawn was promoted to
+    pub promotion: Option<usize>,
+    pub san: String,
+}
+
+impl Game {
+    /// Standard algebraic notation of a move, without promotion and check suffixes. Has to be
+    /// called before the move is played on the board
+    pub fn move_to_san(&self, from: i32, to: i32) -> String {
+        let Some((_, piece)) = self.get_piece(from) else {
+            return String::new();
+        };
+
+        if piece == KING && (from % 8 - to % 8).abs() == 2 {
+            return if to % 8 > from % 8 { "O-O".to_string() } else { "O-O-O".to_string() };
+        }
+
+        let is_capture = self.get_captured_piece(from, to).is_some();
+        let mut san = String::from(PIECE_LETTERS[piece]);
+
+        if piece == PAWN {
+            if is_capture {
+                san.push((b'a' + (from % 8) as u8) as char);
+            }
+        } else {
+            san += &notation::disambiguate(&self.chess, from, to);
+        }
+
+        if is_capture {
+            san.push('x');
+        }
+
+        san + &square_name(to)
+    }
+
+    /// Append the promotion and check markers to the last move once the pawn has been promoted
+    pub fn record_promotion(&mut self, piece: usize) {
+        let suffix = self.check_suffix();
+        if let Some(record) = self.history.last_mut() {
+            record.promotion = Some(piece);
+            record.san += &format!("={}{}", PIECE_LETTERS[piece], suffix);
+        }
+        self.record_position();
+    }
+
+    /// Check or checkmate marker for the position on the board
+    pub fn check_suffix(&self) -> &'static str {
+        match self.chess.get_game_state() {
+            GameState::Check => "+",
+            GameState::CheckMate => "#",
+            _ => "",
+        }
+    }
+
+    /// Find the move described by `text` in standard algebraic notation among the legal moves
+    ///
+    /// Return the from and to slots and the promotion piece as an index into PIECE_NAMES
+    pub fn find_move(&self, text: &str) -> Result<(i32, i32, Option<usize>), String> {
+        let king = match self.chess.get_player_turn() {
+            Turn::White => self.king_index.0,
+            Turn::Black => self.king_index.1,
+        };
+
+        notation::find_move(&self.chess, text, |kingside| {
+            // the rook on the side being castled to, which need not be on the a- or h-file in Chess960
+            self.castling_rooks(king).into_iter()
+                .find(|&rook| (rook > king) == kingside)
+                .and_then(|rook| self.castling_move(king, rook))
+                .map(|to| (king, to))
+        })
+    }
+
+    /// Play a move written in standard algebraic notation
+    pub fn play_san(&mut self, text: &str) -> Result<(), String> {
+        let (from, to, promotion) = self.find_move(text)?;
+
+        if !self.apply_move(from, to) {
+            return Err(format!("Illegal move: {}", text));
+        }
+
+        if let Some(piece) = promotion {
+            if !self.promote(piece) {
+                return Err(format!("Could not promote: {}", text));
+            }
+        }
+
+        Ok(())
+    }
+}
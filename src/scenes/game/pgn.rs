@@ -0,0 +1,231 @@
+use std::{fs, io, path::{Path, PathBuf}, time::{SystemTime, UNIX_EPOCH}};
+
+use crate::chess::fen::{parse_fen, START_FEN};
+use super::{Game, GameSetup};
+
+pub const SAVE_DIRECTORY : &str = "saves";
+
+const LINE_LENGTH : usize = 80;
+const RESULTS : [&str ; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+impl Game {
+    /// The full game as PGN, `score` being the result in PGN form, e.g. "1-0" or "*"
+    pub fn to_pgn(&self, score: &str) -> String {
+        let mut pgn = String::new();
+        let mut tags = vec![
+            ("Event", "Casual game".to_string()),
+            ("Site", "Chess".to_string()),
+            ("Date", today()),
+            ("Round", "-".to_string()),
+            ("White", "White".to_string()),
+            ("Black", "Black".to_string()),
+            ("Result", score.to_string()),
+        ];
+
+        if self.start_fen != START_FEN {
+            tags.push(("SetUp", "1".to_string()));
+            tags.push(("FEN", self.start_fen.clone()));
+        }
+
+        for (name, value) in tags {
+            pgn += &format!("[{} \"{}\"]\n", name, value);
+        }
+        pgn.push('\n');
+
+        let start = parse_fen(&self.start_fen).unwrap();
+        // ply counted from white's move of the starting move number
+        let first_ply = if start.white_to_move { 0 } else { 1 };
+
+        let mut tokens = vec![];
+        for (i, record) in self.history.iter().enumerate() {
+            let ply = i + first_ply;
+            let number = start.fullmove as usize + ply / 2;
+            if ply % 2 == 0 {
+                tokens.push(format!("{}.", number));
+            } else if i == 0 {
+                tokens.push(format!("{}...", number));
+            }
+            tokens.push(record.san.clone());
+        }
+        tokens.push(score.to_string());
+
+        let mut line_length = 0;
+        for token in tokens {
+            if line_length != 0 && line_length + token.len() + 1 > LINE_LENGTH {
+                pgn.push('\n');
+                line_length = 0;
+            } else if line_length != 0 {
+                pgn.push(' ');
+                line_length += 1;
+            }
+
+            line_length += token.len();
+            pgn += &token;
+        }
+        pgn.push('\n');
+
+        pgn
+    }
+}
+
+/// Read the starting position and moves of a game
+pub fn parse_pgn(text: &str) -> Result<GameSetup, String> {
+    let mut fen = None;
+    let mut movetext = String::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            if let Some(value) = tag_value(line, "FEN") {
+                parse_fen(&value)?;
+                fen = Some(value);
+            }
+        } else if !line.starts_with('%') {
+            movetext += line;
+            movetext.push('\n');
+        }
+    }
+
+    // drop comments and variations, only the main line is replayed
+    let mut main_line = String::new();
+    let (mut in_comment, mut in_line_comment, mut variation_depth) = (false, false, 0);
+    for c in movetext.chars() {
+        match c {
+            '\n' => {
+                in_line_comment = false;
+                main_line.push(' ');
+            },
+            _ if in_line_comment => {},
+            '}' if in_comment => in_comment = false,
+            _ if in_comment => {},
+            '{' => in_comment = true,
+            ';' => in_line_comment = true,
+            '(' => variation_depth += 1,
+            ')' => variation_depth -= 1,
+            _ if variation_depth > 0 => {},
+            _ => main_line.push(c),
+        }
+    }
+
+    let mut moves = vec![];
+    for token in main_line.split_whitespace() {
+        if RESULTS.contains(&token) || token.starts_with('$') {
+            continue;
+        }
+
+        // castling may be written with zeros which would otherwise be read as a move number
+        let token = if token.starts_with("0-0") { token } else { token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.') };
+        if !token.is_empty() {
+            moves.push(token.to_string());
+        }
+    }
+
+    Ok(GameSetup { fen, moves })
+}
+
+/// Read a game from a PGN file, or a position from a FEN file
+pub fn load_from_file(path: &Path) -> Result<GameSetup, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+
+    if path.extension().map_or(false, |extension| extension == "fen") {
+        let fen = contents.trim().to_string();
+        parse_fen(&fen)?;
+        Ok(GameSetup { fen: Some(fen), moves: vec![] })
+    } else {
+        parse_pgn(&contents)
+    }
+}
+
+/// Files in the save directory that can be loaded, newest first
+pub fn list_saved_games() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(SAVE_DIRECTORY) else {
+        return vec![];
+    };
+
+    let mut paths : Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().map_or(false, |extension| extension == "pgn" || extension == "fen"))
+        .collect();
+
+    paths.sort();
+    paths.reverse();
+    paths
+}
+
+fn tag_value(line: &str, name: &str) -> Option<String> {
+    let (tag, value) = line.trim_start_matches('[').trim_end_matches(']').split_once(' ')?;
+    if tag != name {
+        return None;
+    }
+
+    Some(value.trim().trim_matches('"').to_string())
+}
+
+/// Write `contents` to a new timestamped file in the save directory
+pub fn save_to_file(contents: &str, extension: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(SAVE_DIRECTORY)?;
+
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let path = PathBuf::from(SAVE_DIRECTORY).join(format!("game-{}.{}", seconds, extension));
+    fs::write(&path, contents)?;
+
+    Ok(path)
+}
+
+/// Current UTC date formatted as YYYY.MM.DD
+fn today() -> String {
+    let days = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() / 86400).unwrap_or(0) as i64;
+
+    // days since epoch to civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_tags_and_moves() {
+        let pgn = "[Event \"Casual game\"]\n[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1\"]\n\n1. e4 Kd7 2. e5 1-0\n";
+        let setup = parse_pgn(pgn).unwrap();
+
+        assert_eq!(setup.fen.as_deref(), Some("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"));
+        assert_eq!(setup.moves, ["e4", "Kd7", "e5"]);
+    }
+
+    #[test]
+    fn skips_comments_variations_and_annotations() {
+        let pgn = "1. e4 {the best move} e5 (1... c5 2. Nf3 {Sicilian}) 2. Nf3 $1 Nc6 ; to defend e5\n3. Bc4 3... Bc5 4. 0-0 *";
+        let setup = parse_pgn(pgn).unwrap();
+
+        assert_eq!(setup.fen, None);
+        assert_eq!(setup.moves, ["e4", "e5", "Nf3", "Nc6", "Bc4", "Bc5", "0-0"]);
+    }
+
+    #[test]
+    fn reads_moves_written_against_their_number() {
+        let setup = parse_pgn("1.d4 d5 2.c4 1/2-1/2").unwrap();
+        assert_eq!(setup.moves, ["d4", "d5", "c4"]);
+    }
+
+    #[test]
+    fn rejects_an_invalid_start_position() {
+        assert!(parse_pgn("[FEN \"not a position\"]\n1. e4").is_err());
+    }
+
+    #[test]
+    fn reads_tag_values() {
+        assert_eq!(tag_value("[White \"Some player\"]", "White").as_deref(), Some("Some player"));
+        assert_eq!(tag_value("[White \"Some player\"]", "Black"), None);
+    }
+}
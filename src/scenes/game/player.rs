@@ -16,8 +16,10 @@ pub enum PlayerTypes {
 }
 
 pub trait Player {
-    /// Caleld when GameState::ongoing
-    fn on_ongoing(&mut self, rl: &mut RaylibHandle);
+    /// Called when GameState::ongoing, `hovered` being the board slot under the mouse
+    fn on_ongoing(&mut self, rl: &mut RaylibHandle, hovered: Option<i32>);
+    /// Called when GameState::ongoing and it is the opponent's turn
+    fn on_waiting(&mut self, rl: &mut RaylibHandle, hovered: Option<i32>);
     /// Called when GameState::Promotion
     ///
     /// Return if to call update_promotion
@@ -25,8 +27,12 @@ pub trait Player {
     /// Called when GameState::End
     fn on_end(&mut self);
     /// Perform operations before attempting to play move and check with server if this is an okay
-    /// move
-    fn on_move_piece(&mut self) -> bool;
+    /// move. `promotion` is the piece a pawn reaching the last rank becomes, as an index into
+    /// PIECE_NAMES, when it is known before the move
+    fn on_move_piece(&mut self, promotion: Option<usize>) -> bool;
+    /// Take the piece the pawn of the last move is promoted to, for players whose moves come
+    /// with it
+    fn take_promotion(&mut self) -> Option<usize>;
 
     /// Get slot to move selected piece to
     fn get_move(&self) -> Option<i32>;
@@ -34,17 +40,50 @@ pub trait Player {
     fn get_selected_slot(&self) -> Option<i32>;
     /// Get current mouse position
     fn get_mouse(&self) -> (i32, i32);
+    /// Whether the selected piece is currently being dragged by the mouse
+    fn is_dragging(&self) -> bool;
 
     fn clear_selected(&mut self);
+    /// Called for a right-click on the board that did not draw an arrow
+    fn on_right_click(&mut self);
+    /// Drop both the selected slot and the slot to move to
+    fn cancel_move(&mut self);
+    /// Select a move entered without the mouse, e.g. typed in algebraic notation
+    ///
+    /// Return false if the player does not take moves from this computer
+    fn queue_move(&mut self, from: i32, to: i32) -> bool;
+    /// Take the oldest move queued while waiting for the opponent
+    fn take_premove(&mut self) -> Option<(i32, i32)>;
+    /// Moves queued while waiting for the opponent, oldest first
+    fn get_premoves(&self) -> &[(i32, i32)];
+
+}
 
+impl PlayerTypes {
+    /// A new player of the same kind for a rematch, None for remote players whose connection
+    /// belongs to the game that ended
+    pub fn rematch(&self) -> Option<PlayerTypes> {
+        match self {
+            Self::Local(_) => Some(Self::Local(LocalPlayer::init())),
+            Self::RemoteSend(_) | Self::RemoteRecv(_) => None,
+        }
+    }
 }
 
 impl Player for PlayerTypes {
-    fn on_ongoing(&mut self, rl: &mut RaylibHandle) {
+    fn on_ongoing(&mut self, rl: &mut RaylibHandle, hovered: Option<i32>) {
+        match self {
+            Self::Local(local) => local.on_ongoing(rl, hovered),
+            Self::RemoteSend(remote_send) => remote_send.on_ongoing(rl, hovered),
+            Self::RemoteRecv(remote_recv) => remote_recv.on_ongoing(rl, hovered),
+        }
+    }
+
+    fn on_waiting(&mut self, rl: &mut RaylibHandle, hovered: Option<i32>) {
         match self {
-            Self::Local(local) => local.on_ongoing(rl),
-            Self::RemoteSend(remote_send) => remote_send.on_ongoing(rl),
-            Self::RemoteRecv(remote_recv) => remote_recv.on_ongoing(rl),
+            Self::Local(local) => local.on_waiting(rl, hovered),
+            Self::RemoteSend(remote_send) => remote_send.on_waiting(rl, hovered),
+            Self::RemoteRecv(remote_recv) => remote_recv.on_waiting(rl, hovered),
         }
     }
 
@@ -64,11 +103,19 @@ impl Player for PlayerTypes {
         }
     }
 
-    fn on_move_piece(&mut self) -> bool {
+    fn on_move_piece(&mut self, promotion: Option<usize>) -> bool {
         match self {
-            Self::Local(local) => local.on_move_piece(),
-            Self::RemoteSend(remote_send) => remote_send.on_move_piece(),
-            Self::RemoteRecv(remote_recv) => remote_recv.on_move_piece(),
+            Self::Local(local) => local.on_move_piece(promotion),
+            Self::RemoteSend(remote_send) => remote_send.on_move_piece(promotion),
+            Self::RemoteRecv(remote_recv) => remote_recv.on_move_piece(promotion),
+        }
+    }
+
+    fn take_promotion(&mut self) -> Option<usize> {
+        match self {
+            Self::Local(local) => local.take_promotion(),
+            Self::RemoteSend(remote_send) => remote_send.take_promotion(),
+            Self::RemoteRecv(remote_recv) => remote_recv.take_promotion(),
         }
     }
 
@@ -96,6 +143,14 @@ impl Player for PlayerTypes {
         }
     }
 
+    fn is_dragging(&self) -> bool {
+        match self {
+            Self::Local(local) => local.is_dragging(),
+            Self::RemoteSend(remote_send) => remote_send.is_dragging(),
+            Self::RemoteRecv(remote_recv) => remote_recv.is_dragging(),
+        }
+    }
+
     fn clear_selected(&mut self) {
         match self {
             Self::Local(local) => local.clear_selected(),
@@ -103,4 +158,44 @@ impl Player for PlayerTypes {
             Self::RemoteRecv(remote_recv) => remote_recv.clear_selected(),
         }
     }
+
+    fn on_right_click(&mut self) {
+        match self {
+            Self::Local(local) => local.on_right_click(),
+            Self::RemoteSend(remote_send) => remote_send.on_right_click(),
+            Self::RemoteRecv(remote_recv) => remote_recv.on_right_click(),
+        }
+    }
+
+    fn cancel_move(&mut self) {
+        match self {
+            Self::Local(local) => local.cancel_move(),
+            Self::RemoteSend(remote_send) => remote_send.cancel_move(),
+            Self::RemoteRecv(remote_recv) => remote_recv.cancel_move(),
+        }
+    }
+
+    fn queue_move(&mut self, from: i32, to: i32) -> bool {
+        match self {
+            Self::Local(local) => local.queue_move(from, to),
+            Self::RemoteSend(remote_send) => remote_send.queue_move(from, to),
+            Self::RemoteRecv(remote_recv) => remote_recv.queue_move(from, to),
+        }
+    }
+
+    fn take_premove(&mut self) -> Option<(i32, i32)> {
+        match self {
+            Self::Local(local) => local.take_premove(),
+            Self::RemoteSend(remote_send) => remote_send.take_premove(),
+            Self::RemoteRecv(remote_recv) => remote_recv.take_premove(),
+        }
+    }
+
+    fn get_premoves(&self) -> &[(i32, i32)] {
+        match self {
+            Self::Local(local) => local.get_premoves(),
+            Self::RemoteSend(remote_send) => remote_send.get_premoves(),
+            Self::RemoteRecv(remote_recv) => remote_recv.get_premoves(),
+        }
+    }
 }
@@ -1,5 +1,4 @@
 use raylib::prelude::*;
-use crate::scenes::game::screen::screen_to_board_coord;
 
 use super::Player;
 
@@ -7,43 +6,47 @@ pub struct LocalPlayer {
     selected: Option<i32>,
     move_to: Option<i32>,
     mouse: (i32, i32),
+    // board slot under the mouse
+    hovered: Option<i32>,
+    dragging: bool,
+    // true when the press that started the drag landed on an already selected piece
+    reselected: bool,
 }
 
 impl Player for LocalPlayer {
-    fn on_ongoing(&mut self, rl: &mut RaylibHandle) {
+    fn on_ongoing(&mut self, rl: &mut RaylibHandle, hovered: Option<i32>) {
         self.mouse = (rl.get_mouse_x(), rl.get_mouse_y());
-        if !rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
-            return;
-        }
+        self.hovered = hovered;
 
-        let (x, y) = screen_to_board_coord(self.mouse.0, self.mouse.1);
-
-        if !(0..8).contains(&x) || !(0..8).contains(&y) {
+        // a right-click snaps a dragged piece back right away, other right-clicks may still draw
+        // an arrow and are handled once released
+        if self.dragging && rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT) {
+            self.cancel_move();
             return;
         }
 
-        if let Some(selected_index) = self.selected {
-            if selected_index != y * 8 + x {
-                self.move_to = Some(y * 8 + x);
-            } else {
-                self.on_move_piece();
-            }
-        } else {
-            self.selected = Some(y * 8 + x);
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.on_press();
+        } else if self.dragging && rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.on_release();
         }
     }
 
+    // both sides play on this computer, so there is never a reason to premove
+    fn on_waiting(&mut self, _: &mut RaylibHandle, _: Option<i32>) {}
+
     fn on_promotion(&mut self) -> bool {true}
-    fn on_end(&mut self) {
-        println!("end");
-    }
+    fn on_end(&mut self) { }
 
-    fn on_move_piece(&mut self) -> bool {
+    fn on_move_piece(&mut self, _: Option<usize>) -> bool {
         self.clear_selected();
         self.move_to = None;
         true
     }
 
+    // the piece is picked on the board once the engine asks for it
+    fn take_promotion(&mut self) -> Option<usize> { None }
+
     fn get_move(&self) -> Option<i32> {
         self.move_to
     }
@@ -56,10 +59,34 @@ impl Player for LocalPlayer {
         self.mouse
     }
 
+    fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
     fn clear_selected(&mut self) {
         self.selected = None;
+        self.dragging = false;
+    }
+
+    fn on_right_click(&mut self) {
+        self.cancel_move();
+    }
+
+    fn cancel_move(&mut self) {
+        self.clear_selected();
+        self.move_to = None;
     }
 
+    fn queue_move(&mut self, from: i32, to: i32) -> bool {
+        self.selected = Some(from);
+        self.move_to = Some(to);
+        self.dragging = false;
+        true
+    }
+
+    fn take_premove(&mut self) -> Option<(i32, i32)> { None }
+    fn get_premoves(&self) -> &[(i32, i32)] { &[] }
+
 }
 
 impl LocalPlayer {
@@ -68,6 +95,42 @@ impl LocalPlayer {
             selected: None,
             move_to: None,
             mouse: (0, 0),
+            hovered: None,
+            dragging: false,
+            reselected: false,
+        }
+    }
+
+    fn on_press(&mut self) {
+        let Some(index) = self.hovered else {
+            return;
+        };
+
+        match self.selected {
+            Some(selected_index) if selected_index != index => self.move_to = Some(index),
+            Some(_) => {
+                self.dragging = true;
+                self.reselected = true;
+            },
+            None => {
+                self.selected = Some(index);
+                self.dragging = true;
+                self.reselected = false;
+            }
+        }
+    }
+
+    fn on_release(&mut self) {
+        self.dragging = false;
+
+        match self.hovered {
+            Some(index) if Some(index) != self.selected => self.move_to = Some(index),
+            // released on the square it was picked up from, treat it as a click
+            Some(_) => if self.reselected {
+                self.clear_selected();
+            },
+            // dropped outside of the board, snap the piece back
+            None => self.clear_selected(),
         }
     }
 }
@@ -3,51 +3,66 @@ use std::{io::{self, ErrorKind, Read}, net::TcpStream};
 use chess_networking::{Ack, Move};
 use raylib::RaylibHandle;
 
+use crate::{chess::from_network_promotion, ui::toast};
+
 use super::Player;
 
 pub struct RemoteRecvPlayer {
     from: Option<i32>,
     to: Option<i32>,
-    attempted_move: bool,
+    // piece the opponent's last move promotes to, as an index into PIECE_NAMES
+    promotion: Option<usize>,
+    // the opponent is told about once, the stream keeps reading nothing after that
+    disconnected: bool,
     stream: TcpStream
 }
 
 impl Player for RemoteRecvPlayer { 
-    fn on_ongoing(&mut self, rl: &mut RaylibHandle) {
-        if self.attempted_move {
-            println!("ack[ ok: {} ]", !(self.from.is_some() && self.to.is_some()));
-            self.attempted_move = false;
+    fn on_ongoing(&mut self, _: &mut RaylibHandle, _: Option<i32>) {
+        if self.disconnected {
+            return;
         }
 
-
         let mut buf = [0u8; 1024];
         match self.stream.read(&mut buf) {
-            Ok(size) => {
-                if size == 0 {
-                    return;
-                }
+            Ok(0) => {
+                self.disconnected = true;
+                toast::warning("Your opponent disconnected");
+                return;
             },
-
+            Ok(_) => {},
+            // nothing was sent this frame
+            Err(e) if e.kind() == ErrorKind::WouldBlock => return,
             Err(e) => {
-                println!("Error(RecvPlayer ongoing): {}", e);
+                self.disconnected = true;
+                toast::error(format!("Lost the connection to the server: {}", e));
                 return;
             }
         }
         
         if let Ok(_move) = Move::try_from(&buf[..]) {
+            if _move.offer_draw {
+                toast::info("Your opponent offers a draw");
+            }
+
             self.from = Some(_move.from.1 as i32 * 8 + _move.from.0 as i32);
             self.to = Some(_move.to.1 as i32 * 8 + _move.to.0 as i32);
-            self.attempted_move = true;
+            self.promotion = _move.promotion.as_ref().map(from_network_promotion);
         }
     }
+    fn on_waiting(&mut self, _: &mut RaylibHandle, _: Option<i32>) {}
     fn on_promotion(&mut self) -> bool { false }
     fn on_end(&mut self) { }
-    fn on_move_piece(&mut self) -> bool {
+    fn on_move_piece(&mut self, _: Option<usize>) -> bool {
         self.clear_selected();
         self.to = None;
         true
     }
 
+    fn take_promotion(&mut self) -> Option<usize> {
+        self.promotion.take()
+    }
+
     fn get_move(&self) -> Option<i32> {
         self.to
     }
@@ -57,9 +72,19 @@ impl Player for RemoteRecvPlayer {
     }
 
     fn get_mouse(&self) -> (i32, i32) { (-1, -1) }
+    fn is_dragging(&self) -> bool { false }
     fn clear_selected(&mut self) {
         self.from = None;
     }
+    // the opponent's moves are not picked here
+    fn on_right_click(&mut self) {}
+    fn cancel_move(&mut self) {
+        self.clear_selected();
+        self.to = None;
+    }
+    fn queue_move(&mut self, _: i32, _: i32) -> bool { false }
+    fn take_premove(&mut self) -> Option<(i32, i32)> { None }
+    fn get_premoves(&self) -> &[(i32, i32)] { &[] }
 }
 
 impl RemoteRecvPlayer {
@@ -68,7 +93,8 @@ impl RemoteRecvPlayer {
         RemoteRecvPlayer {
             from: None,
             to: None,
-            attempted_move: false,
+            promotion: None,
+            disconnected: false,
             stream
         }
     }
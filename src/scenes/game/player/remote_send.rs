@@ -1,53 +1,75 @@
 
-use std::{io::{Read, Write}, mem, net::TcpStream};
+use std::{io::{Read, Write}, net::TcpStream};
 
 use chess_networking::{Ack, Move};
 use raylib::prelude::*;
 
+use crate::{chess::to_network_promotion, ui::toast};
+
 use super::{local::LocalPlayer, Player};
 
 pub struct RemoteSendPlayer {
     local: LocalPlayer,
+    premoves: Vec<(i32, i32)>,
     stream: TcpStream
 }
 
 impl Player for RemoteSendPlayer { 
-    fn on_ongoing(&mut self, rl: &mut RaylibHandle) {
-        self.local.on_ongoing(rl);
+    fn on_ongoing(&mut self, rl: &mut RaylibHandle, hovered: Option<i32>) {
+        self.local.on_ongoing(rl, hovered);
+    }
+    fn on_waiting(&mut self, rl: &mut RaylibHandle, hovered: Option<i32>) {
+        // moves are picked like normal but queued instead of sent, legality is checked once it
+        // is our turn
+        self.local.on_ongoing(rl, hovered);
+        if let (Some(from), Some(to)) = (self.local.get_selected_slot(), self.local.get_move()) {
+            self.premoves.push((from, to));
+            self.local.cancel_move();
+        }
     }
     fn on_promotion(&mut self) -> bool { false }
     fn on_end(&mut self) { }
-    fn on_move_piece(&mut self) -> bool {
+    fn on_move_piece(&mut self, promotion: Option<usize>) -> bool {
         if let (Some(from), Some(to)) = (self.get_selected_slot(), self.get_move()) {
             let move_obj = Move {
                 from: (from as u8 % 8, from as u8 / 8),
                 to: (to as u8 % 8, to as u8 / 8),
-                promotion: None,
+                promotion: promotion.and_then(to_network_promotion),
                 offer_draw: false,
                 forfeit: false,
             };
 
-            println!("Sending move to server");
             {
                 let buf : Vec<u8> = move_obj.try_into().unwrap();
-                self.stream.write_all(&buf).unwrap();
+                if let Err(e) = self.stream.write_all(&buf) {
+                    toast::error(format!("Could not send the move: {}", e));
+                    self.local.cancel_move();
+                    return false;
+                }
             }
-            println!("Sent success!");
 
-            println!("Watiting for ack from server");
-            {
-                let mut buf = [0u8 ; mem::size_of::<Ack>()];
+            let ack = {
+                // large enough for the whole encoded answer, which is longer than the struct
+                let mut buf = [0u8 ; 1024];
                 let _ = self.stream.set_nonblocking(false);
-                loop {
-                    if self.stream.read(&mut buf).unwrap() != 0 {
-                        break;
-                    }
-                }
+                let read = self.stream.read(&mut buf);
                 let _ = self.stream.set_nonblocking(true);
+                match read {
+                    Ok(0) => Err("the server closed the connection".to_string()),
+                    Ok(size) => Ack::try_from(&buf[..size]).map_err(|_| "the server sent an invalid answer".to_string()),
+                    Err(e) => Err(e.to_string()),
+                }
+            };
+
+            match ack {
+                Ok(ack) if ack.ok => {
+                    self.local.on_move_piece(promotion);
+                    return true;
+                },
+                Ok(_) => toast::warning("The server rejected the move"),
+                Err(e) => toast::error(format!("No answer to the move: {}", e)),
             }
-            println!("Ack recieved!");
-            self.local.on_move_piece();
-            return true;
+            self.local.cancel_move();
         }
 
         false
@@ -62,9 +84,34 @@ impl Player for RemoteSendPlayer {
     fn get_mouse(&self) -> (i32, i32) { 
         self.local.get_mouse()
     }
+    fn is_dragging(&self) -> bool {
+        self.local.is_dragging()
+    }
     fn clear_selected(&mut self) {
         self.local.clear_selected();
     }
+    fn on_right_click(&mut self) {
+        self.local.on_right_click();
+        self.premoves.clear();
+    }
+    fn cancel_move(&mut self) {
+        self.local.cancel_move();
+    }
+    fn queue_move(&mut self, from: i32, to: i32) -> bool {
+        self.local.queue_move(from, to)
+    }
+    // the piece was picked before the move was sent
+    fn take_promotion(&mut self) -> Option<usize> { None }
+    fn take_premove(&mut self) -> Option<(i32, i32)> {
+        if self.premoves.is_empty() {
+            None
+        } else {
+            Some(self.premoves.remove(0))
+        }
+    }
+    fn get_premoves(&self) -> &[(i32, i32)] {
+        &self.premoves
+    }
 }
 
 impl RemoteSendPlayer {
@@ -72,6 +119,7 @@ impl RemoteSendPlayer {
         let _ = stream.set_nonblocking(true);
         RemoteSendPlayer {
             local: LocalPlayer::init(),
+            premoves: vec![],
             stream
         }
     }
@@ -4,8 +4,11 @@ use raylib::color::{Color as RayColor};
 use viktoe_chess::board::Turn;
 use viktoe_chess::piece::Piece;
 
-use super::{piece_to_texture, Game};
-use super::screen::{board_coord_to_screen, screen_to_board_coord, TILE_SIZE};
+use crate::chess::{piece_index, promotion_piece};
+
+use super::player::Player;
+use super::{draw_piece, piece_to_texture, Game};
+use super::screen::Screen;
 
 const PROMOTION_PIECETYPES : [Piece; 4] = [Piece::Queen, Piece::Rook, Piece::Knight, Piece::Bishop];
 
@@ -13,39 +16,76 @@ impl Game {
     pub fn draw_promotion(&self, draw_handler: &mut RaylibDrawHandle) {
         if let Some(promotion_slot) = self.promoted_slot {
             let textures = match self.player_turn {
-                Turn::White => &self.white_textures,
-                Turn::Black => &self.black_textures,
+                Turn::White => &self.pieces.white,
+                Turn::Black => &self.pieces.black,
             };
 
             let (px, py) = (promotion_slot as usize % 8, promotion_slot as usize / 8);
             let color = self.colors[(px + (py & 1) + 1) & 1];
             for (i, piece) in PROMOTION_PIECETYPES.iter().enumerate() {
-                draw_promotion_square_with_texture(draw_handler, piece_to_texture(textures, piece), color, 9, 5 - i as i32);
+                draw_promotion_square_with_texture(draw_handler, &self.screen, piece_to_texture(textures, piece), color, 9, 5 - i as i32);
             }
         }
     }
 
     pub fn update_promotion(&mut self, rl: &mut RaylibHandle) {
-        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
-            let (x, y) = screen_to_board_coord(rl.get_mouse_x(), rl.get_mouse_y());
+        if let Some(piece) = pick_promotion(rl, &self.screen) {
+            self.promote(piece);
+        }
+    }
 
-            if x != 9 || !(2..=5).contains(&y) {
-                return;
-            }
+    /// Wait for a remote player to pick the piece a move promotes to before it is sent, since
+    /// the server gets the piece along with the move
+    pub fn update_promotion_move(&mut self, rl: &mut RaylibHandle) {
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) || rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT) {
+            self.promotion_move = None;
+            self.get_player_mut().cancel_move();
+        } else if let Some(piece) = pick_promotion(rl, &self.screen) {
+            // the move is still selected, and goes on to be played with the piece next frame
+            self.promotion_move = None;
+            self.typed_promotion = Some(piece);
+        }
+    }
+
+    /// Whether the move from `from` to `to` takes a pawn to the last rank
+    pub fn promotes(&self, from: i32, to: i32) -> bool {
+        matches!(self.get_piece(from), Some((_, 0))) && (to / 8 == 0 || to / 8 == 7)
+    }
 
-            match PROMOTION_PIECETYPES[(5 - y) as usize] {
-                Piece::Queen => self.chess.promote_pawn(Piece::Queen),
-                Piece::Rook => self.chess.promote_pawn(Piece::Rook),
-                Piece::Knight => self.chess.promote_pawn(Piece::Knight),
-                Piece::Bishop => self.chess.promote_pawn(Piece::Bishop),
-                _ => return,
-            }.unwrap();
+    /// Promote the waiting pawn to the piece with the given index into PIECE_NAMES
+    ///
+    /// Return if the promotion was made
+    pub fn promote(&mut self, piece: usize) -> bool {
+        let Some(promotion) = promotion_piece(piece) else {
+            return false;
+        };
+
+        if self.chess.promote_pawn(promotion).is_err() {
+            return false;
         }
+
+        self.record_promotion(piece);
+        true
+    }
+}
+
+/// Index into PIECE_NAMES of the piece clicked in the promotion column
+fn pick_promotion(rl: &RaylibHandle, screen: &Screen) -> Option<usize> {
+    if !rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+        return None;
+    }
+
+    let (x, y) = screen.screen_to_grid(rl.get_mouse_x(), rl.get_mouse_y());
+    if x != 9 || !(2..=5).contains(&y) {
+        return None;
     }
+
+    Some(piece_index(&PROMOTION_PIECETYPES[(5 - y) as usize]))
 }
 
-fn draw_promotion_square_with_texture(draw_handler: &mut RaylibDrawHandle, texture: &Texture2D, color: RayColor, x: i32, y: i32) {
-    let (px, py) = board_coord_to_screen(x, y);
-    draw_handler.draw_rectangle(px, py, TILE_SIZE, TILE_SIZE, color);
-    draw_handler.draw_texture(texture, px, py, RayColor::WHITE);
+fn draw_promotion_square_with_texture(draw_handler: &mut RaylibDrawHandle, screen: &Screen, texture: &Texture2D, color: RayColor, x: i32, y: i32) {
+    let (px, py) = screen.grid_to_screen(x, y);
+    let tile_size = screen.tile_size();
+    draw_handler.draw_rectangle(px, py, tile_size, tile_size, color);
+    draw_piece(draw_handler, texture, px, py, tile_size);
 }
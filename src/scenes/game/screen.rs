@@ -1,20 +1,85 @@
-use crate::{HEIGHT, WIDTH};
+use raylib::RaylibHandle;
 
-pub const TILE_SIZE : i32 = 60;
+/// Resolution the piece textures are drawn at, they are scaled to the tile size when drawn
 pub const IMAGE_SIZE : i32 = 60;
-pub const ATTACK_CIRCLE_RADIUS : i32 = 10;
 
-pub const BOARD_SIZE : i32 = TILE_SIZE * 8;
-pub const BOARD_OFFSET_X : i32 = (WIDTH - BOARD_SIZE) / 2;
-pub const BOARD_OFFSET_Y : i32 = (HEIGHT - BOARD_SIZE) / 2;
+const MIN_TILE_SIZE : i32 = 24;
+// Room around the board for the side buttons, the capture trays, the turn bar and the move input
+const BOARD_MARGIN_X : i32 = 210;
+const BOARD_MARGIN_Y : i32 = 110;
+// The promotion picker sits in the second column right of the board
+const GRID_COLUMNS : i32 = 12;
 
-pub fn board_coord_to_screen(x: i32, y: i32) -> (i32, i32) {
-    (x * IMAGE_SIZE + BOARD_OFFSET_X, (7 - y) * IMAGE_SIZE + BOARD_OFFSET_Y)
+/// Size of the window the board is laid out in, the board is centered in it and its tiles are
+/// as large as fits
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Screen {
+    width: i32,
+    height: i32,
 }
 
-pub fn screen_to_board_coord(x: i32, y: i32) -> (i32, i32) {
-    let diff_x = x - BOARD_OFFSET_X;
-    let diff_y = y - BOARD_OFFSET_Y;
-    if diff_x < 0 || diff_y < 0 { (-1, -1) }
-    else { (diff_x / IMAGE_SIZE, 7 - diff_y / IMAGE_SIZE) }
+impl Screen {
+    pub fn new(width: i32, height: i32) -> Self {
+        Self { width, height }
+    }
+
+    /// The window as it is this frame
+    pub fn of(rl: &RaylibHandle) -> Self {
+        Self::new(rl.get_screen_width(), rl.get_screen_height())
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// Largest tile size that fits the board and everything around it in the window
+    pub fn tile_size(&self) -> i32 {
+        ((self.width - 2 * BOARD_MARGIN_X) / 8)
+            .min((self.height - 2 * BOARD_MARGIN_Y) / 8)
+            .min(self.width / GRID_COLUMNS)
+            .max(MIN_TILE_SIZE)
+    }
+
+    pub fn board_size(&self) -> i32 {
+        self.tile_size() * 8
+    }
+
+    pub fn board_offset_x(&self) -> i32 {
+        (self.width - self.board_size()) / 2
+    }
+
+    pub fn board_offset_y(&self) -> i32 {
+        (self.height - self.board_size()) / 2
+    }
+
+    pub fn attack_circle_radius(&self) -> i32 {
+        self.tile_size() / 6
+    }
+
+    /// Screen position of a board slot, black being drawn at the bottom when `flipped`
+    pub fn board_coord_to_screen(&self, x: i32, y: i32, flipped: bool) -> (i32, i32) {
+        if flipped { self.grid_to_screen(7 - x, 7 - y) } else { self.grid_to_screen(x, y) }
+    }
+
+    pub fn screen_to_board_coord(&self, x: i32, y: i32, flipped: bool) -> (i32, i32) {
+        let (x, y) = self.screen_to_grid(x, y);
+        if x < 0 || y < 0 || x > 7 || y > 7 { (-1, -1) }
+        else if flipped { (7 - x, 7 - y) }
+        else { (x, y) }
+    }
+
+    /// Screen position of a tile in the grid the board is laid out in, not affected by the
+    /// orientation of the board. Used for things next to the board like the promotion picker
+    pub fn grid_to_screen(&self, x: i32, y: i32) -> (i32, i32) {
+        let tile_size = self.tile_size();
+        (x * tile_size + self.board_offset_x(), (7 - y) * tile_size + self.board_offset_y())
+    }
+
+    pub fn screen_to_grid(&self, x: i32, y: i32) -> (i32, i32) {
+        let tile_size = self.tile_size();
+        let diff_x = x - self.board_offset_x();
+        let diff_y = y - self.board_offset_y();
+        if diff_x < 0 || diff_y < 0 { (-1, -1) }
+        else { (diff_x / tile_size, 7 - diff_y / tile_size) }
+    }
 }
@@ -1,4 +1,7 @@
+use std::path::Path;
+
 use raylib::prelude::*;
+use raylib::color::Color as RayColor;
 
 use viktoe_chess::piece::Piece;
 use viktoe_chess::piece::Color;
@@ -6,28 +9,24 @@ use viktoe_chess::position::BoardPosition;
 
 use crate::scenes::SceneStorage;
 
+use crate::chess::{notation::PIECE_LETTERS, piece_index, PIECE_COUNT, PIECE_NAMES};
+use crate::ui::toast;
+use super::screen::IMAGE_SIZE;
 use super::Game;
 
-pub const PIECE_COUNT : usize = 6;
-pub const PIECE_NAMES : [&str ; PIECE_COUNT] = ["pawn", "bishop", "knight", "rook", "queen", "king"];
+/// Directory of the piece set shipped with the game
+pub const DEFAULT_PIECE_SET : &str = "textures";
 
 pub fn piece_to_texture<'a>(textures: &'a [Texture2D ; PIECE_COUNT], piece: &Piece) -> &'a Texture2D {
-     match piece {
-        Piece::Pawn { .. } =>   &textures[0],
-        Piece::Bishop =>        &textures[1],
-        Piece::Knight =>        &textures[2],
-        Piece::Rook =>          &textures[3],
-        Piece::Queen =>         &textures[4],
-        Piece::King { .. } =>   &textures[5],
-    }
+    &textures[piece_index(piece)]
 }
 
 impl Game {
     pub fn get_texture_for_square(&self, x: u8, y: u8) -> Option<&Texture2D> {
         if let Some(colored_piece) = self.chess.get_square(&BoardPosition::try_from((x, y)).unwrap()) {
             let (piece, textures) = match colored_piece {
-                Color::White(piece) => (piece, &self.white_textures),
-                Color::Black(piece) => (piece, &self.black_textures),
+                Color::White(piece) => (piece, &self.pieces.white),
+                Color::Black(piece) => (piece, &self.pieces.black),
             };
 
             Some(piece_to_texture(textures, piece))
@@ -37,6 +36,44 @@ impl Game {
     }
 }
 
-pub fn load_piece_texture(rl: &mut RaylibHandle, thread: &RaylibThread, color: &'static str, name: &'static str) -> Texture2D {
-    rl.load_texture(thread, &("textures/".to_string() + color + "_" + name + ".png")).unwrap()
+/// Draw a piece texture scaled to fill a `size` wide square
+pub fn draw_piece(draw_handler: &mut RaylibDrawHandle, texture: &Texture2D, x: i32, y: i32, size: i32) {
+    let scale = size as f32 / texture.width() as f32;
+    draw_handler.draw_texture_ex(texture, Vector2 { x: x as f32, y: y as f32 }, 0.0, scale, RayColor::WHITE);
+}
+
+pub fn load_piece_set(rl: &mut RaylibHandle, thread: &RaylibThread, directory: &str, color: &'static str) -> [Texture2D ; PIECE_COUNT] {
+    PIECE_NAMES.map(|name| load_piece_texture(rl, thread, directory, color, name))
+}
+
+/// Load `{directory}/{color}_{name}.png`, falling back to the default piece set and then to a
+/// drawn placeholder when the file is missing or can't be read
+pub fn load_piece_texture(rl: &mut RaylibHandle, thread: &RaylibThread, directory: &str, color: &'static str, name: &'static str) -> Texture2D {
+    for directory in [directory, DEFAULT_PIECE_SET] {
+        let path = Path::new(directory).join(color.to_string() + "_" + name + ".png");
+        match rl.load_texture(thread, &path.to_string_lossy()) {
+            Ok(texture) => return texture,
+            Err(e) => toast::warning(format!("Could not load {}: {}", path.display(), e)),
+        }
+    }
+
+    let (fill, outline) = match color {
+        "white" => (RayColor::WHITE, RayColor::BLACK),
+        _ => (RayColor::BLACK, RayColor::WHITE),
+    };
+
+    // a disc with the piece letter, pawns have no letter in algebraic notation
+    let letter = match PIECE_LETTERS[PIECE_NAMES.iter().position(|&n| n == name).unwrap_or(0)] {
+        "" => "P",
+        letter => letter,
+    };
+
+    let mut image = Image::gen_image_color(IMAGE_SIZE, IMAGE_SIZE, RayColor::BLANK);
+    image.draw_circle(IMAGE_SIZE / 2, IMAGE_SIZE / 2, IMAGE_SIZE / 2 - 4, outline);
+    image.draw_circle(IMAGE_SIZE / 2, IMAGE_SIZE / 2, IMAGE_SIZE / 2 - 6, fill);
+    let font_size = IMAGE_SIZE / 2;
+    let width = measure_text(letter, font_size);
+    image.draw_text(letter, (IMAGE_SIZE - width) / 2, (IMAGE_SIZE - font_size) / 2, font_size, outline);
+
+    rl.load_texture_from_image(thread, &image).expect("no graphics context to create textures in")
 }
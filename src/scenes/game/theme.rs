@@ -0,0 +1,126 @@
+use std::fs;
+
+use raylib::color::Color as RayColor;
+
+use crate::{settings::SharedSettings, ui::toast};
+
+use super::textures::DEFAULT_PIECE_SET;
+
+pub const THEME_FILE : &str = "themes.cfg";
+
+// [white, black, selected, attackable, turn bar, king in check, premove], the same order as Game::colors
+pub const THEME_COLOR_COUNT : usize = 7;
+const COLOR_KEYS : [&str ; THEME_COLOR_COUNT] = ["white", "black", "selected", "attackable", "turn", "check", "premove"];
+
+/// Colors of the board and the directory the piece textures are loaded from
+#[derive(Clone)]
+pub struct Theme {
+    pub name: String,
+    pub colors: [u32 ; THEME_COLOR_COUNT],
+    pub piece_set: String,
+}
+
+impl Theme {
+    fn new(name: &str, colors: [u32 ; THEME_COLOR_COUNT]) -> Self {
+        Self {
+            name: name.to_string(),
+            colors,
+            piece_set: DEFAULT_PIECE_SET.to_string(),
+        }
+    }
+
+    pub fn ray_colors(&self) -> [RayColor ; THEME_COLOR_COUNT] {
+        self.colors.map(RayColor::get_color)
+    }
+}
+
+pub fn builtin_themes() -> Vec<Theme> {
+    vec![
+        Theme::new("Classic", [0xedd6b0ff, 0xb88762ff, 0xdbc34aff, 0x05050540, 0xebc334ff, 0xf55742ff, 0x4a7fdb90]),
+        Theme::new("Forest", [0xeeeed2ff, 0x769656ff, 0xbaca44ff, 0x05050540, 0xbaca44ff, 0xe0443aff, 0x3f6fc090]),
+        Theme::new("Ocean", [0xdee3e6ff, 0x8ca2adff, 0x9bc7e0ff, 0x05050540, 0x5fa8d3ff, 0xf55742ff, 0xc0703f90]),
+        Theme::new("Slate", [0xc8c8c8ff, 0x6e6e6eff, 0xe0c060ff, 0x05050560, 0xe0c060ff, 0xf55742ff, 0x4a7fdb90]),
+        Theme::new("Rose", [0xf4e1e1ff, 0xc48b9fff, 0xf0b95cff, 0x05050540, 0xd06c8cff, 0xe63b3bff, 0x5b7fd690]),
+    ]
+}
+
+/// Built-in themes followed by the ones in the theme file. A theme in the file with the name of a
+/// built-in one replaces it
+pub fn load_themes() -> Vec<Theme> {
+    let mut themes = builtin_themes();
+
+    let Ok(contents) = fs::read_to_string(THEME_FILE) else {
+        return themes;
+    };
+
+    for theme in parse_themes(&contents) {
+        match themes.iter().position(|other| other.name == theme.name) {
+            Some(index) => themes[index] = theme,
+            None => themes.push(theme),
+        }
+    }
+
+    themes
+}
+
+/// Read themes written as
+///
+/// ```text
+/// [Name]
+/// white = edd6b0ff
+/// black = b88762ff
+/// pieces = textures
+/// ```
+///
+/// Colors are RGBA in hex, missing colors are taken from the first built-in theme. Lines that
+/// can't be read are reported and skipped
+pub fn parse_themes(text: &str) -> Vec<Theme> {
+    let defaults = builtin_themes().remove(0);
+    let mut themes : Vec<Theme> = vec![];
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            let mut theme = defaults.clone();
+            theme.name = line[1..line.len() - 1].trim().to_string();
+            themes.push(theme);
+            continue;
+        }
+
+        let (Some(theme), Some((key, value))) = (themes.last_mut(), line.split_once('=')) else {
+            toast::warning(format!("{}:{}: expected a [theme] or key = value", THEME_FILE, number + 1));
+            continue;
+        };
+
+        let (key, value) = (key.trim(), value.trim());
+        if key == "pieces" {
+            theme.piece_set = value.to_string();
+            continue;
+        }
+
+        match (COLOR_KEYS.iter().position(|&color_key| color_key == key), u32::from_str_radix(value.trim_start_matches('#'), 16)) {
+            (Some(index), Ok(color)) => theme.colors[index] = color,
+            (Some(_), Err(_)) => toast::warning(format!("{}:{}: invalid color {}", THEME_FILE, number + 1, value)),
+            (None, _) => toast::warning(format!("{}:{}: unknown key {}", THEME_FILE, number + 1, key)),
+        }
+    }
+
+    themes
+}
+
+/// Use the theme for the rest of the session, it is only kept between runs once saved in the settings
+pub fn select_theme(settings: &SharedSettings, name: &str) {
+    let mut selected = settings.get();
+    selected.theme = name.to_string();
+    settings.set(selected);
+}
+
+/// Index of the selected theme in `themes`, the first one if it no longer exists
+pub fn selected_theme_index(themes: &[Theme], settings: &SharedSettings) -> usize {
+    let name = settings.get().theme;
+    themes.iter().position(|theme| theme.name == name).unwrap_or(0)
+}
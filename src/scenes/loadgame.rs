@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use button::Button;
+
+use super::{game::{pgn::{list_saved_games, load_from_file}, player::{local::LocalPlayer, PlayerTypes}}, Scene, SceneInitType};
+use crate::{resources::Resources, ui::{input::Input, list::ListView, layout::{Align, Edges, Layout, Size}, widgets::{dispatch, Event, Widgets}, *}};
+
+const FONT_SIZE : i32 = 45;
+const INPUT_BOTTOM_PADDING : i32 = 60;
+
+const BACK_BUTTON_PADDING : i32 = 10;
+const BACK_BUTTON_SIZE : i32 = 50;
+
+const INPUT_HEIGHT : i32 = FONT_SIZE + 20;
+
+const LIST_TOP : i32 = 90;
+const LIST_BOTTOM_PADDING : i32 = 160;
+// The list takes 5 ninths of the width of the window, the input and mode button line up with it
+const LIST_SHARE : (u32, u32) = (5, 9);
+const ROW_HEIGHT : i32 = 30;
+const ROW_FONT_SIZE : i32 = 20;
+const ROW_TEXT_PADDING : i32 = 10;
+const HEADING_FONT_SIZE : i32 = ROW_FONT_SIZE + 5;
+
+const MESSAGE_FONT_SIZE : i32 = 20;
+
+const MODE_BUTTON_WIDTH : i32 = 200;
+const MODE_BUTTON_HEIGHT : i32 = 40;
+const MODE_FONT_SIZE : i32 = 20;
+
+/// File picker listing the games in the save directory, any other file can be typed into the input
+pub struct LoadGame {
+    files: Vec<PathBuf>,
+    message: Option<String>,
+    // open the game in the replay viewer instead of continuing it
+    replay: bool,
+    widgets: Widgets<Self>,
+    input_id: usize,
+    list_id: usize,
+    mode_button_id: usize,
+    heading_font: WeakFont,
+    message_font: WeakFont,
+}
+
+impl Scene for LoadGame {
+    fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) {
+        let (window_width, window_height) = (draw_handler.get_screen_width(), draw_handler.get_screen_height());
+        let left = list_left(window_width);
+        draw_text(draw_handler, &self.heading_font, "Saved games", left, LIST_TOP - ROW_HEIGHT - ROW_TEXT_PADDING, HEADING_FONT_SIZE, RayColor::WHITE);
+
+        if let Some(message) = &self.message {
+            let (width, _) = measure(&self.message_font, message, MESSAGE_FONT_SIZE);
+            draw_text(draw_handler, &self.message_font, message, (window_width - width) / 2, list_bottom(window_height) + ROW_TEXT_PADDING, MESSAGE_FONT_SIZE, RayColor::get_color(0xf5_57_42_ff));
+        }
+
+        self.widgets.draw(draw_handler);
+    }
+
+    fn update(&mut self, rl: &mut raylib::RaylibHandle, _: &RaylibThread) -> SceneInitType {
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            return SceneInitType::Start;
+        }
+
+        let callbacks = self.widgets.update(rl);
+        dispatch(self, rl, callbacks)
+    }
+}
+
+impl LoadGame {
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources) -> Self {
+        let mut input = Input::init(resources.ui_font(rl, thread, FONT_SIZE), FONT_SIZE);
+        input.set_placeholder("Path to a game");
+        input.set_bg_color(RayColor::get_color(0xff_ff_ff_0a));
+        input.set_selected_bg_color(RayColor::get_color(0xff_ff_ff_1a));
+        input.set_border_color(RayColor::get_color(0xff_ff_ff_a0));
+
+        let mut back_button = Button::new(resources.ui_font(rl, thread, FONT_SIZE));
+        back_button.set_text("<", FONT_SIZE);
+
+        let files = list_saved_games();
+        let mut list = ListView::new(resources.ui_font(rl, thread, ROW_FONT_SIZE), ROW_FONT_SIZE, ROW_HEIGHT);
+        list.set_items(files.iter().map(|path| path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()).collect());
+        list.set_empty_text("No saved games found");
+
+        let mut mode_button = Button::new(resources.ui_font(rl, thread, MODE_FONT_SIZE));
+        mode_button.set_text(mode_text(false), MODE_FONT_SIZE);
+
+        let mut widgets = Widgets::new();
+        let back_button_id = widgets.add(back_button);
+        let input_id = widgets.add(input);
+        let list_id = widgets.add(list);
+        let mode_button_id = widgets.add(mode_button);
+
+        widgets.on(back_button_id, Event::Clicked, |_, _| SceneInitType::Start);
+        widgets.on(input_id, Event::Submitted, |scene: &mut Self, _| {
+            let path = scene.widgets.text(scene.input_id);
+            scene.load(Path::new(path.trim()))
+        });
+        widgets.on(list_id, Event::Clicked, |scene: &mut Self, _| {
+            match scene.widgets.list(scene.list_id).and_then(|list| list.get_selected()) {
+                Some(index) => {
+                    let path = scene.files[index].clone();
+                    scene.load(&path)
+                },
+                None => SceneInitType::None,
+            }
+        });
+        widgets.on(mode_button_id, Event::Clicked, |scene: &mut Self, _| {
+            scene.replay = !scene.replay;
+            if let Some(button) = scene.widgets.button_mut(scene.mode_button_id) {
+                button.set_text(mode_text(scene.replay), MODE_FONT_SIZE);
+            }
+            SceneInitType::None
+        });
+
+        // the list fills the middle of the window, the input sits under it and the mode button over its right end, both as wide as
+        // the list allows
+        let column = Layout::overlay([
+            Layout::widget(list_id).margin(Edges::new(0, LIST_TOP, 0, LIST_BOTTOM_PADDING)),
+            Layout::widget(input_id).height(Size::Fixed(INPUT_HEIGHT)).anchor(Align::Start, Align::End).margin(Edges::new(0, 0, 0, INPUT_BOTTOM_PADDING)),
+            Layout::row([Layout::space(), Layout::widget(mode_button_id).width(Size::Fixed(MODE_BUTTON_WIDTH))])
+                .height(Size::Fixed(MODE_BUTTON_HEIGHT))
+                .margin(Edges::new(0, LIST_TOP - MODE_BUTTON_HEIGHT - ROW_TEXT_PADDING / 2, 0, 0)),
+        ]);
+        widgets.set_layout(rl, Layout::overlay([
+            Layout::widget(back_button_id).size(BACK_BUTTON_SIZE, BACK_BUTTON_SIZE).margin(Edges::all(BACK_BUTTON_PADDING)),
+            Layout::centered(column, LIST_SHARE.0, LIST_SHARE.1),
+        ]));
+
+        LoadGame {
+            files,
+            message: None,
+            replay: false,
+            widgets,
+            input_id,
+            list_id,
+            mode_button_id,
+            heading_font: resources.ui_font(rl, thread, HEADING_FONT_SIZE),
+            message_font: resources.ui_font(rl, thread, MESSAGE_FONT_SIZE),
+        }
+    }
+
+    fn load(&mut self, path: &Path) -> SceneInitType {
+        match load_from_file(path) {
+            Ok(setup) if self.replay => SceneInitType::Replay(setup),
+            Ok(setup) => SceneInitType::GameFrom([PlayerTypes::Local(LocalPlayer::init()), PlayerTypes::Local(LocalPlayer::init())], setup),
+            Err(e) => {
+                self.message = Some(e);
+                SceneInitType::None
+            }
+        }
+    }
+}
+
+/// Left of the list as `Layout::centered` places it
+fn list_left(window_width: i32) -> i32 {
+    window_width * (LIST_SHARE.1 - LIST_SHARE.0) as i32 / (2 * LIST_SHARE.1) as i32
+}
+
+fn list_bottom(window_height: i32) -> i32 {
+    window_height - LIST_BOTTOM_PADDING
+}
+
+fn mode_text(replay: bool) -> &'static str {
+    if replay { "Open to replay" } else { "Open to play" }
+}
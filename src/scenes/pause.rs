@@ -0,0 +1,84 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use crate::{resources::Resources, ui::{button::Button, draw_text, layout::{Align, Edges, Layout}, measure, widgets::{dispatch, Event, Widgets}}};
+
+use super::{Scene, SceneInitType};
+
+const BACKDROP_COLOR : u32 = 0x00_00_00_a0;
+const PANEL_WIDTH : i32 = 320;
+const PANEL_HEIGHT : i32 = 230;
+const PANEL_COLOR : u32 = 0x10_10_10_e0;
+
+const TITLE_FONT_SIZE : i32 = 40;
+const TEXT_PADDING : i32 = 20;
+
+const BUTTON_WIDTH : i32 = 240;
+const BUTTON_HEIGHT : i32 = 45;
+const BUTTON_SPACING : i32 = 10;
+const BUTTON_FONT_SIZE : i32 = 30;
+
+/// Menu pushed over a running game, which keeps drawing underneath and carries on where it was
+/// once the menu is popped
+pub struct Pause {
+    widgets: Widgets<Self>,
+    title_font: WeakFont,
+}
+
+impl Scene for Pause {
+    fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) {
+        let (width, height) = (draw_handler.get_screen_width(), draw_handler.get_screen_height());
+        draw_handler.draw_rectangle(0, 0, width, height, RayColor::get_color(BACKDROP_COLOR));
+
+        let (left, top) = ((width - PANEL_WIDTH) / 2, (height - PANEL_HEIGHT) / 2);
+        draw_handler.draw_rectangle(left, top, PANEL_WIDTH, PANEL_HEIGHT, RayColor::get_color(PANEL_COLOR));
+
+        let (title_width, _) = measure(&self.title_font, "Paused", TITLE_FONT_SIZE);
+        draw_text(draw_handler, &self.title_font, "Paused", (width - title_width) / 2, top + TEXT_PADDING, TITLE_FONT_SIZE, RayColor::WHITE);
+
+        self.widgets.draw(draw_handler);
+    }
+
+    fn update(&mut self, rl: &mut RaylibHandle, _: &RaylibThread) -> SceneInitType {
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            return SceneInitType::Pop;
+        }
+
+        let callbacks = self.widgets.update(rl);
+        dispatch(self, rl, callbacks)
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+impl Pause {
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources) -> Self {
+        let mut widgets = Widgets::new();
+
+        let resume_button_id = widgets.add(create_button(rl, thread, resources, "Resume"));
+        widgets.on(resume_button_id, Event::Clicked, |_, _| SceneInitType::Pop);
+
+        let menu_button_id = widgets.add(create_button(rl, thread, resources, "Main menu"));
+        widgets.on(menu_button_id, Event::Clicked, |_, _| SceneInitType::Start);
+
+        widgets.focus(resume_button_id);
+
+        // the buttons are stacked in the panel under the title
+        let buttons = [resume_button_id, menu_button_id].map(|id| Layout::widget(id).size(BUTTON_WIDTH, BUTTON_HEIGHT).anchor(Align::Center, Align::Start));
+        widgets.set_layout(rl, Layout::column(buttons)
+            .size(PANEL_WIDTH, PANEL_HEIGHT)
+            .anchor(Align::Center, Align::Center)
+            .padding(Edges::new(0, TEXT_PADDING * 2 + TITLE_FONT_SIZE, 0, 0))
+            .spacing(BUTTON_SPACING));
+
+        Pause { widgets, title_font: resources.ui_font(rl, thread, TITLE_FONT_SIZE) }
+    }
+}
+
+fn create_button(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, text: &'static str) -> Button {
+    let mut button = Button::new(resources.ui_font(rl, thread, BUTTON_FONT_SIZE));
+    button.set_text(text, BUTTON_FONT_SIZE);
+    button
+}
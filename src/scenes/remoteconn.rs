@@ -1,4 +1,4 @@
-use std::{collections::HashMap, io::Write, net::TcpStream, os::unix::thread};
+use std::{io::Write, net::TcpStream, os::unix::thread};
 
 use chess_networking::Start;
 use raylib::prelude::*;
@@ -7,9 +7,13 @@ use raylib::color::Color as RayColor;
 use button::Button;
 
 use super::{Scene, SceneInitType};
-use crate::{ui::{input::Input, *}, HEIGHT, WIDTH};
+use crate::{resources::Resources, settings::SharedSettings, ui::{input::Input, layout::{Align, Edges, Layout, Size}, widgets::{dispatch, Event, Widgets}, *}};
 
+// Parts of the width of the window the play button and the input take
+const PLAY_BUTTON_SHARE : (u32, u32) = (1, 2);
 const PLAY_BUTTON_HEIGHT : i32 = 75;
+const INPUT_SHARE : (u32, u32) = (5, 9);
+const INPUT_HEIGHT : i32 = FONT_SIZE + 20;
 const FONT_SIZE : i32 = 45;
 const PLAY_BUTTON_BOTTOM_PADDING : i32 = 10;
 
@@ -17,15 +21,17 @@ const BACK_BUTTON_PADDING : i32 = 10;
 const BACK_BUTTON_SIZE : i32 = 50;
 
 pub struct RemoteConn {
-    elements: [UIElement; 3],
-    actions: HashMap<usize, Box<dyn Fn(&Self) -> SceneInitType>>
+    // position to ask the server to start from
+    fen: Option<String>,
+    // name sent to the server
+    player_name: String,
+    widgets: Widgets<Self>,
+    input_id: usize,
 }
 
 impl Scene for RemoteConn {
     fn draw(&mut self, draw_handler: &mut raylib::prelude::RaylibDrawHandle) {
-        for element in &self.elements {
-            element.draw(draw_handler);
-        }
+        self.widgets.draw(draw_handler);
     }
 
     fn update(&mut self, rl: &mut raylib::RaylibHandle, thread: &RaylibThread) -> SceneInitType {
@@ -33,97 +39,95 @@ impl Scene for RemoteConn {
             return SceneInitType::Start;
         }
 
-        for element in &mut self.elements {
-            if element.update(rl) {
-                let id = element.get_id();
-                if let Some(action) = self.actions.get(&id) {
-                    return action(self);
-                }
-            }
-        }
-
-        SceneInitType::None
+        let callbacks = self.widgets.update(rl);
+        dispatch(self, rl, callbacks)
     }
 }
 
 impl RemoteConn {
-    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread) -> Self {
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, settings: &SharedSettings, fen: Option<String>) -> Self {
+        let settings = settings.get();
+
         let input_bg_color = RayColor::get_color(0xff_ff_ff_0a);
         let input_selected_color = RayColor::get_color(0xff_ff_ff_1a);
         let input_border_color = RayColor::get_color(0xff_ff_ff_a0);
 
-        let mut input = Input::init(rl.get_font_default(), FONT_SIZE);
-        let input_id = input.get_id();
-        input.center_vertical_height(FONT_SIZE + 20);
-        input.center_horizontal_width(5 * WIDTH / 9);
+        let mut input = Input::init(resources.ui_font(rl, thread, FONT_SIZE), FONT_SIZE);
+        input.set_text(&settings.server_address);
+        input.set_placeholder("host:port");
+        input.set_validator(valid_address);
         input.set_bg_color(input_bg_color);
         input.set_selected_bg_color(input_selected_color);
         input.set_border_color(input_border_color);
 
-        let mut play_button = Button::new(rl.get_font_default());
-        let play_button_id = play_button.get_id();
-        play_button.center_horizontal_width(WIDTH / 2);
-        play_button.set_top(HEIGHT - PLAY_BUTTON_HEIGHT - PLAY_BUTTON_BOTTOM_PADDING);
-        play_button.set_height(PLAY_BUTTON_HEIGHT);
+        let mut play_button = Button::new(resources.ui_font(rl, thread, FONT_SIZE));
         play_button.set_text("Connect and play", FONT_SIZE);
 
-        let mut back_button = Button::new(rl.get_font_default());
-        let back_button_id = back_button.get_id();
-        back_button.set_top(BACK_BUTTON_PADDING);
-        back_button.set_left(BACK_BUTTON_PADDING);
-        back_button.set_width(BACK_BUTTON_SIZE);
-        back_button.set_height(BACK_BUTTON_SIZE);
+        let mut back_button = Button::new(resources.ui_font(rl, thread, FONT_SIZE));
         back_button.set_text("<", FONT_SIZE);
 
-        let mut remote_conn = RemoteConn {
-            elements: [UIElement::Button(play_button), UIElement::Button(back_button), UIElement::Input(input)],
-            actions: HashMap::new()
-        };
+        // added in the order Tab goes through them, starting on the address
+        let mut widgets = Widgets::new();
+        let input_id = widgets.add(input);
+        let play_button_id = widgets.add(play_button);
+        let back_button_id = widgets.add(back_button);
+        widgets.focus(input_id);
 
-        let conn = |scene: &Self| {
-            if let UIElement::Input(input) = &scene.elements[2] {
-                connect(input)
-            } else {
-                SceneInitType::None
-            }
+        let conn = |scene: &mut Self, _: &mut RaylibHandle| match scene.widgets.input(scene.input_id) {
+            Some(input) if input.is_valid() => connect(input, scene.fen.clone(), scene.player_name.clone()),
+            _ => SceneInitType::None,
         };
 
-        remote_conn.add_action(play_button_id, Box::new(conn));
-        remote_conn.add_action(input_id, Box::new(conn));
-
-        remote_conn.add_action(back_button_id, Box::new(|_: &Self| SceneInitType::Start));
-
-        remote_conn
+        widgets.on(play_button_id, Event::Clicked, conn);
+        widgets.on(input_id, Event::Submitted, conn);
+        widgets.on(back_button_id, Event::Clicked, |_, _| SceneInitType::Start);
+
+        // the back button stays in the corner, the rest follows the size of the window
+        widgets.set_layout(rl, Layout::overlay([
+            Layout::widget(back_button_id).size(BACK_BUTTON_SIZE, BACK_BUTTON_SIZE).margin(Edges::all(BACK_BUTTON_PADDING)),
+            Layout::centered(Layout::widget(input_id), INPUT_SHARE.0, INPUT_SHARE.1).height(Size::Fixed(INPUT_HEIGHT)).anchor(Align::Center, Align::Center),
+            Layout::centered(Layout::widget(play_button_id), PLAY_BUTTON_SHARE.0, PLAY_BUTTON_SHARE.1).height(Size::Fixed(PLAY_BUTTON_HEIGHT)).anchor(Align::Center, Align::End).margin(Edges::new(0, 0, 0, PLAY_BUTTON_BOTTOM_PADDING)),
+        ]));
+
+        RemoteConn {
+            fen,
+            player_name: settings.player_name,
+            widgets,
+            input_id,
+        }
     }
+}
 
-    fn add_action(&mut self, id: usize, action: Box<dyn Fn(&Self) -> SceneInitType>) {
-        self.actions.insert(id, action);
+/// Whether `text` looks like `host:port`, the host isn't looked up until connecting
+pub fn valid_address(text: &str) -> bool {
+    match text.trim().rsplit_once(':') {
+        Some((host, port)) => !host.is_empty() && !host.contains(char::is_whitespace) && port.parse::<u16>().is_ok(),
+        None => false,
     }
 }
 
-pub fn connect(input: &Input) -> SceneInitType {
-    let address = input.get_text();
+pub fn connect(input: &Input, fen: Option<String>, name: String) -> SceneInitType {
+    let address = input.get_text().trim();
     match TcpStream::connect(address) {
         Ok(mut stream) => {
             let start = Start {
                 is_white: true,
-                name: Some("Zimon".to_string()),
-                fen: None,
+                name: Some(name),
+                fen,
                 time: None,
                 inc: None,
             };
 
             let buf : Vec<u8> = start.try_into().unwrap();
             if let Err(e) = stream.write_all(&buf) {
-                println!("\nError sending start to server: {}\n", e);
+                toast::error(format!("Could not start a game on {}: {}", address, e));
                 SceneInitType::None
             } else {
                 SceneInitType::RemoteGame(stream)
             }
         },
         Err(err) => {
-            println!("Error occured trying to connect to: {}", address);
-            println!("{}", err);
+            toast::error(format!("Could not connect to {}: {}", address, err));
             SceneInitType::None
         }
     }
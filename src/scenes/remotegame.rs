@@ -1,12 +1,12 @@
-use std::{collections::HashMap, io::Read, mem, net::TcpStream, time::Duration};
+use std::{io::{ErrorKind, Read}, net::TcpStream, time::Duration};
 
 use chess_networking::Start;
 use raylib::prelude::*;
 use raylib::color::Color as RayColor;
 
-use crate::ui::{button::Button, label::Label, UIElement, UIElementTrait};
+use crate::{resources::Resources, settings::SharedSettings, ui::{button::Button, label::Label, layout::{Edges, Layout}, toast, widgets::{dispatch, Event, Widgets}}};
 
-use super::{game::{player::{remote_recv::RemoteRecvPlayer, remote_send::RemoteSendPlayer, PlayerTypes}, Game}, Scene, SceneInitType};
+use super::{game::{player::{remote_recv::RemoteRecvPlayer, remote_send::RemoteSendPlayer, PlayerTypes}, Game, GameSetup}, Scene, SceneInitType};
 
 const STR_1 : &str = "Waiting for game";
 const STR_2 : &str = "Waiting for game.";
@@ -17,10 +17,12 @@ const BACK_BUTTON_PADDING : i32 = 10;
 const BACK_BUTTON_SIZE : i32 = 50;
 
 pub struct RemoteGame {
+    // the game is only created once the server starts it
     game: Option<Game>,
     stream: TcpStream,
-    elements: [UIElement; 2],
-    actions: HashMap<usize, Box<dyn Fn(&Self) -> SceneInitType>>
+    resources: Resources,
+    settings: SharedSettings,
+    widgets: Widgets<Self>,
 }
 
 impl Scene for RemoteGame {
@@ -28,83 +30,92 @@ impl Scene for RemoteGame {
         if let Some(game) = &mut self.game {
             game.draw(draw_handler);
         } else {
-            for element in &self.elements {
-                element.draw(draw_handler);
-            }
+            self.widgets.draw(draw_handler);
         }
     }
 
     fn update(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) -> SceneInitType {
         if let Some(game) = &mut self.game {
-            game.update(rl, thread);
-        } else {
-            if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
-                return SceneInitType::RemoteConn;
-            }
-
-            self.update_connection(rl, thread);
-            for element in &mut self.elements {
-                if element.update(rl) {
-                    let id = element.get_id();
-                    if let Some(action) = self.actions.get(&id) {
-                        return action(self);
-                    }
-                }
-            }
+            return game.update(rl, thread);
         }
 
-        SceneInitType::None
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            return SceneInitType::RemoteConn(None);
+        }
+
+        let next = self.update_connection(rl, thread);
+        if !matches!(next, SceneInitType::None) {
+            return next;
+        }
+
+        let callbacks = self.widgets.update(rl);
+        dispatch(self, rl, callbacks)
     }
 }
 
 impl RemoteGame {
-    pub fn init(rl: &mut RaylibHandle, stream: TcpStream) -> Self {
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, settings: &SharedSettings, stream: TcpStream) -> Self {
         // make sure that any stream IO will not be blocking
         stream.set_nonblocking(true).unwrap();
 
-        let mut label = Label::new(rl.get_font_default(), 50);
+        let mut label = Label::new(resources.ui_font(rl, thread, 50), 50);
         label.add_text(STR_1);
         label.add_text(STR_2);
         label.add_text(STR_3);
         label.add_text(STR_4);
         label.set_index(0);
-        label.center_vertically();
-        label.center_horizontally();
         label.set_color(RayColor::WHITE);
         label.set_timeout(Duration::from_secs(1));
 
-        let mut back_button = Button::new(rl.get_font_default());
-        let back_button_id = back_button.get_id();
-        back_button.set_top(BACK_BUTTON_PADDING);
-        back_button.set_left(BACK_BUTTON_PADDING);
-        back_button.set_width(BACK_BUTTON_SIZE);
-        back_button.set_height(BACK_BUTTON_SIZE);
+        let mut back_button = Button::new(resources.ui_font(rl, thread, 45));
         back_button.set_text("<", 45);
 
-        let mut rgame = RemoteGame {
-            game: None,
-            stream,
-            elements: [UIElement::Label(label), UIElement::Button(back_button)],
-            actions: HashMap::new()
-        };
+        let mut widgets = Widgets::new();
+        let label_id = widgets.add(label);
+        let back_button_id = widgets.add(back_button);
+        widgets.on(back_button_id, Event::Clicked, |_, _| SceneInitType::RemoteConn(None));
 
-        rgame.add_action(back_button_id, Box::new(|_: &Self| SceneInitType::RemoteConn));
+        // the label is centered in the whole window
+        widgets.set_layout(rl, Layout::overlay([
+            Layout::widget(label_id),
+            Layout::widget(back_button_id).size(BACK_BUTTON_SIZE, BACK_BUTTON_SIZE).margin(Edges::all(BACK_BUTTON_PADDING)),
+        ]));
 
-        rgame
-    }
-
-    fn add_action(&mut self, id: usize, action: Box<dyn Fn(&Self) -> SceneInitType>) {
-        self.actions.insert(id, action);
+        RemoteGame {
+            game: None,
+            stream,
+            resources: resources.clone(),
+            settings: settings.clone(),
+            widgets,
+        }
     }
 
-    pub fn update_connection(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) {
-        let mut buf = [0u8; mem::size_of::<Start>()];
-        let _ = self.stream.read(&mut buf);
+    /// Start the game once the server sends the start, going back to the connection scene if
+    /// the server goes away first
+    pub fn update_connection(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) -> SceneInitType {
+        // large enough for a start carrying a FEN
+        let mut buf = [0u8; 1024];
+        match self.stream.read(&mut buf) {
+            Ok(0) => {
+                toast::error("The server closed the connection before the game started");
+                return SceneInitType::RemoteConn(None);
+            },
+            Err(e) if e.kind() != ErrorKind::WouldBlock => {
+                toast::error(format!("Lost the connection to the server: {}", e));
+                return SceneInitType::RemoteConn(None);
+            },
+            _ => {},
+        }
 
         if let Ok(start) = chess_networking::Start::try_from(buf.as_slice()) {
             let send_player = PlayerTypes::RemoteSend(RemoteSendPlayer::init(self.stream.try_clone().unwrap()));
             let recv_player = PlayerTypes::RemoteRecv(RemoteRecvPlayer::init(self.stream.try_clone().unwrap()));
-            self.game = Some(Game::init(rl, thread, if start.is_white { [send_player, recv_player] } else { [recv_player, send_player] }));
+            let players = if start.is_white { [send_player, recv_player] } else { [recv_player, send_player] };
+            let mut game = Game::init_from(rl, thread, &self.resources, &self.settings, players, GameSetup { fen: start.fen.clone(), moves: vec![] });
+            // keep our own pieces at the bottom
+            game.set_flipped(!start.is_white);
+            self.game = Some(game);
         }
+        SceneInitType::None
     }
 }
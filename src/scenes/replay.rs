@@ -0,0 +1,341 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use button::Button;
+
+use super::game::{
+    player::{local::LocalPlayer, PlayerTypes},
+    screen::Screen,
+    Game, GameSetup,
+};
+use super::{Scene, SceneInitType};
+use crate::{chess::fen::parse_fen, resources::Resources, settings::SharedSettings, ui::{layout::{Align, Edges, Layout, Rect, Size}, widgets::{dispatch, Event, Widgets}, *}};
+
+// Seconds between moves while playing automatically, slowest first
+const AUTOPLAY_SECONDS : [f32 ; 5] = [3.0, 2.0, 1.0, 0.5, 0.25];
+const DEFAULT_SPEED : usize = 2;
+
+const SIDE_BUTTON_LEFT : i32 = 30;
+const SIDE_BUTTON_WIDTH : i32 = 150;
+const SIDE_BUTTON_HEIGHT : i32 = 45;
+const SIDE_BUTTON_SPACING : i32 = 15;
+const SIDE_BUTTON_FONT_SIZE : i32 = 25;
+const SPEED_FONT_SIZE : i32 = 20;
+
+const NAVIGATION_OFFSET : i32 = 60;
+const NAVIGATION_SIZE : i32 = 50;
+const NAVIGATION_SPACING : i32 = 10;
+
+const BACK_BUTTON_PADDING : i32 = 10;
+const BACK_BUTTON_SIZE : i32 = 50;
+
+const LIST_MARGIN : i32 = 20;
+const ROW_HEIGHT : i32 = 24;
+const ROW_FONT_SIZE : i32 = 18;
+const NUMBER_WIDTH : i32 = 40;
+const MOVE_WIDTH : i32 = 70;
+const CURRENT_MOVE_COLOR : u32 = 0xeb_c3_34_80;
+
+#[derive(Clone, Copy)]
+enum Control {
+    Autoplay,
+    Slower,
+    Faster,
+    First,
+    Previous,
+    Next,
+    Last,
+}
+
+/// Step through a finished game, drawn with the board of `Game`
+pub struct Replay {
+    game: Game,
+    fen: Option<String>,
+    // every move of the game that could be replayed, in algebraic notation
+    moves: Vec<String>,
+    // number of moves played on the board
+    ply: usize,
+    autoplay: bool,
+    speed: usize,
+    // counted down with the frame time so that the pace doesn't depend on the frame rate
+    seconds_until_step: f32,
+    scroll: usize,
+    // window the board is laid out in, as of the last update or draw
+    screen: Screen,
+    widgets: Widgets<Self>,
+    autoplay_button_id: usize,
+    speed_font: WeakFont,
+    row_font: WeakFont,
+}
+
+impl Scene for Replay {
+    fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) {
+        self.screen = Screen::of(draw_handler);
+        self.game.draw_board(draw_handler);
+        self.draw_move_list(draw_handler);
+
+        let speed = format!("{} s per move", AUTOPLAY_SECONDS[self.speed]);
+        draw_text(draw_handler, &self.speed_font, &speed, SIDE_BUTTON_LEFT, side_button_top(&self.screen, 4), SPEED_FONT_SIZE, RayColor::WHITE);
+
+        self.widgets.draw(draw_handler);
+
+        self.game.draw_message(draw_handler);
+    }
+
+    fn update(&mut self, rl: &mut RaylibHandle, _: &RaylibThread) -> SceneInitType {
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            return SceneInitType::LoadGame;
+        }
+
+        self.screen = Screen::of(rl);
+        self.game.advance_animations(rl.get_frame_time());
+
+        let keys = [
+            (KeyboardKey::KEY_SPACE, Control::Autoplay),
+            (KeyboardKey::KEY_DOWN, Control::Slower),
+            (KeyboardKey::KEY_UP, Control::Faster),
+            (KeyboardKey::KEY_HOME, Control::First),
+            (KeyboardKey::KEY_LEFT, Control::Previous),
+            (KeyboardKey::KEY_RIGHT, Control::Next),
+            (KeyboardKey::KEY_END, Control::Last),
+        ];
+        for (key, control) in keys {
+            // Space activates the focused widget rather than starting autoplay as well
+            if key == KeyboardKey::KEY_SPACE && self.widgets.has_focus() {
+                continue;
+            }
+            if rl.is_key_pressed(key) {
+                self.apply_control(control);
+            }
+        }
+
+        if self.autoplay {
+            self.seconds_until_step -= rl.get_frame_time();
+            if self.seconds_until_step <= 0.0 {
+                self.go_to(self.ply + 1);
+                self.reset_step_timer();
+            }
+        }
+
+        self.update_move_list(rl);
+
+        let callbacks = self.widgets.update(rl);
+        dispatch(self, rl, callbacks)
+    }
+}
+
+impl Replay {
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, settings: &SharedSettings, setup: GameSetup) -> Self {
+        // the game notation is normalised by replaying it once, moves after one that can't be
+        // played are dropped and reported by the game
+        let mut game = Game::init_from(rl, thread, resources, settings, [PlayerTypes::Local(LocalPlayer::init()), PlayerTypes::Local(LocalPlayer::init())], setup.clone());
+        let moves = game.move_list();
+        let fen = setup.fen;
+
+        // a game that couldn't be replayed in full starts at the last playable move, where the
+        // game shows why the rest was dropped
+        let ply = if moves.len() == setup.moves.len() { 0 } else { moves.len() };
+        if ply == 0 {
+            game.set_position(fen.as_deref(), &[]);
+        }
+
+        let mut widgets = Widgets::new();
+
+        let mut back_button = Button::new(resources.ui_font(rl, thread, 45));
+        back_button.set_text("<", 45);
+        let back_button_id = widgets.add(back_button);
+        widgets.on(back_button_id, Event::Clicked, |_, _| SceneInitType::LoadGame);
+
+        let mut side_button_ids = [0 ; 4];
+        for (index, (text, control)) in [("Autoplay", Some(Control::Autoplay)), ("Slower", Some(Control::Slower)), ("Faster", Some(Control::Faster)), ("Branch", None)].into_iter().enumerate() {
+            let mut button = Button::new(resources.ui_font(rl, thread, SIDE_BUTTON_FONT_SIZE));
+            button.set_text(text, SIDE_BUTTON_FONT_SIZE);
+            side_button_ids[index] = widgets.add(button);
+
+            match control {
+                Some(control) => widgets.on(side_button_ids[index], Event::Clicked, move |scene: &mut Self, _| {
+                    scene.apply_control(control);
+                    SceneInitType::None
+                }),
+                None => widgets.on(side_button_ids[index], Event::Clicked, |scene: &mut Self, _| {
+                    let setup = GameSetup { fen: scene.fen.clone(), moves: scene.moves[..scene.ply].to_vec() };
+                    SceneInitType::GameFrom([PlayerTypes::Local(LocalPlayer::init()), PlayerTypes::Local(LocalPlayer::init())], setup)
+                }),
+            }
+        }
+
+        let mut navigation_ids = [0 ; 4];
+        for (index, (text, control)) in [("|<", Control::First), ("<", Control::Previous), (">", Control::Next), (">|", Control::Last)].into_iter().enumerate() {
+            let mut button = Button::new(resources.ui_font(rl, thread, SIDE_BUTTON_FONT_SIZE));
+            button.set_text(text, SIDE_BUTTON_FONT_SIZE);
+            navigation_ids[index] = widgets.add(button);
+            widgets.on(navigation_ids[index], Event::Clicked, move |scene: &mut Self, _| {
+                scene.apply_control(control);
+                SceneInitType::None
+            });
+        }
+
+        // side buttons in a column left of the board, navigation centered under it
+        let side_buttons = side_button_ids.map(|id| Layout::widget(id).height(Size::Fixed(SIDE_BUTTON_HEIGHT)));
+        let navigation = navigation_ids.map(|id| Layout::widget(id).width(Size::Fixed(NAVIGATION_SIZE)));
+        widgets.set_layout(rl, Layout::overlay([
+            Layout::widget(back_button_id).size(BACK_BUTTON_SIZE, BACK_BUTTON_SIZE).margin(Edges::all(BACK_BUTTON_PADDING)),
+            Layout::within(|window| {
+                let screen = Screen::new(window.width, window.height);
+                Rect::new(SIDE_BUTTON_LEFT, screen.board_offset_y(), SIDE_BUTTON_WIDTH, screen.board_size())
+            }, Layout::column(side_buttons).spacing(SIDE_BUTTON_SPACING)),
+            Layout::within(|window| {
+                Rect::new(window.left, navigation_top(&Screen::new(window.width, window.height)), window.width, NAVIGATION_SIZE)
+            }, Layout::row(navigation).spacing(NAVIGATION_SPACING).justify(Align::Center)),
+        ]));
+
+        let mut replay = Replay {
+            game,
+            fen,
+            moves,
+            ply,
+            autoplay: false,
+            speed: DEFAULT_SPEED,
+            seconds_until_step: 0.0,
+            scroll: 0,
+            screen: Screen::of(rl),
+            widgets,
+            autoplay_button_id: side_button_ids[0],
+            speed_font: resources.ui_font(rl, thread, SPEED_FONT_SIZE),
+            row_font: resources.ui_font(rl, thread, ROW_FONT_SIZE),
+        };
+
+        replay.go_to(ply);
+
+        replay
+    }
+
+    fn apply_control(&mut self, control: Control) {
+        match control {
+            Control::Autoplay => {
+                self.autoplay = !self.autoplay;
+                // starting again from the last move replays the game from the start
+                if self.autoplay && self.ply == self.moves.len() {
+                    self.go_to(0);
+                }
+                self.reset_step_timer();
+                if let Some(button) = self.widgets.button_mut(self.autoplay_button_id) {
+                    button.set_text(if self.autoplay { "Pause" } else { "Autoplay" }, SIDE_BUTTON_FONT_SIZE);
+                }
+            },
+            Control::Slower => self.speed = self.speed.saturating_sub(1),
+            Control::Faster => self.speed = (self.speed + 1).min(AUTOPLAY_SECONDS.len() - 1),
+            Control::First => self.go_to(0),
+            Control::Previous => self.go_to(self.ply.saturating_sub(1)),
+            Control::Next => self.go_to(self.ply + 1),
+            Control::Last => self.go_to(self.moves.len()),
+        }
+    }
+
+    /// Show the position after `ply` moves, stopping autoplay at the end of the game
+    fn go_to(&mut self, ply: usize) {
+        let ply = ply.min(self.moves.len());
+        if ply == self.moves.len() && self.autoplay {
+            self.apply_control(Control::Autoplay);
+        }
+
+        if ply != self.ply {
+            self.ply = ply;
+            self.game.set_position(self.fen.as_deref(), &self.moves[..ply]);
+        }
+
+        // keep the current move in view
+        let row = self.move_row(ply.saturating_sub(1));
+        let visible = visible_rows(&self.screen);
+        if row < self.scroll {
+            self.scroll = row;
+        } else if row >= self.scroll + visible {
+            self.scroll = row + 1 - visible;
+        }
+    }
+
+    fn reset_step_timer(&mut self) {
+        self.seconds_until_step = AUTOPLAY_SECONDS[self.speed];
+    }
+
+    /// 1 when black moved first, so that white's moves always sit in the first column
+    fn first_column(&self) -> usize {
+        match parse_fen(self.game.get_start_fen()) {
+            Ok(fields) if !fields.white_to_move => 1,
+            _ => 0,
+        }
+    }
+
+    fn move_row(&self, index: usize) -> usize {
+        (index + self.first_column()) / 2
+    }
+
+    fn draw_move_list(&self, draw_handler: &mut RaylibDrawHandle) {
+        let (left, top) = (list_left(&self.screen), self.screen.board_offset_y());
+        let first_column = self.first_column();
+        let fullmove = parse_fen(self.game.get_start_fen()).map_or(1, |fields| fields.fullmove as usize);
+
+        let rows = (self.moves.len() + first_column + 1) / 2;
+        for row in self.scroll..rows.min(self.scroll + visible_rows(&self.screen)) {
+            let y = top + (row - self.scroll) as i32 * ROW_HEIGHT;
+            let text_y = y + (ROW_HEIGHT - ROW_FONT_SIZE) / 2;
+            draw_text(draw_handler, &self.row_font, &format!("{}.", fullmove + row), left, text_y, ROW_FONT_SIZE, RayColor::GRAY);
+
+            for column in 0..2 {
+                let Some(index) = (row * 2 + column).checked_sub(first_column).filter(|&index| index < self.moves.len()) else {
+                    continue;
+                };
+
+                let x = left + NUMBER_WIDTH + column as i32 * MOVE_WIDTH;
+                if index + 1 == self.ply {
+                    draw_handler.draw_rectangle(x - 4, y, MOVE_WIDTH, ROW_HEIGHT, RayColor::get_color(CURRENT_MOVE_COLOR));
+                }
+                draw_text(draw_handler, &self.row_font, &self.moves[index], x, text_y, ROW_FONT_SIZE, RayColor::WHITE);
+            }
+        }
+    }
+
+    /// Scroll the move list and jump to a move when it is clicked
+    fn update_move_list(&mut self, rl: &mut RaylibHandle) {
+        let (x, y) = (rl.get_mouse_x(), rl.get_mouse_y());
+        let (left, top) = (list_left(&self.screen), self.screen.board_offset_y());
+        let width = NUMBER_WIDTH + 2 * MOVE_WIDTH;
+        if !(left..left + width).contains(&x) || !(top..top + self.screen.board_size()).contains(&y) {
+            return;
+        }
+
+        let rows = (self.moves.len() + self.first_column() + 1) / 2;
+        let wheel = rl.get_mouse_wheel_move();
+        if wheel < 0.0 {
+            self.scroll = (self.scroll + 1).min(rows.saturating_sub(visible_rows(&self.screen)));
+        } else if wheel > 0.0 {
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+
+        if !rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) || x < left + NUMBER_WIDTH {
+            return;
+        }
+
+        let row = self.scroll + ((y - top) / ROW_HEIGHT) as usize;
+        let column = ((x - left - NUMBER_WIDTH) / MOVE_WIDTH) as usize;
+        if let Some(index) = (row * 2 + column).checked_sub(self.first_column()).filter(|&index| index < self.moves.len()) {
+            self.go_to(index + 1);
+        }
+    }
+}
+
+fn side_button_top(screen: &Screen, index: i32) -> i32 {
+    screen.board_offset_y() + index * (SIDE_BUTTON_HEIGHT + SIDE_BUTTON_SPACING)
+}
+
+fn navigation_top(screen: &Screen) -> i32 {
+    screen.board_offset_y() + screen.board_size() + NAVIGATION_OFFSET
+}
+
+fn list_left(screen: &Screen) -> i32 {
+    screen.board_offset_x() + screen.board_size() + LIST_MARGIN
+}
+
+fn visible_rows(screen: &Screen) -> usize {
+    (screen.board_size() / ROW_HEIGHT).max(1) as usize
+}
@@ -0,0 +1,226 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use button::Button;
+
+use super::{remoteconn::valid_address, Scene, SceneInitType};
+use crate::{resources::Resources, settings::{AnimationSpeed, Orientation, SharedSettings}};
+use crate::ui::{checkbox::Checkbox, dropdown::Dropdown, input::Input, slider::Slider, layout::{Align, Edges, Layout, Size}, widgets::{dispatch, Event, Widgets}, *};
+
+const FONT_SIZE : i32 = 25;
+const TITLE_FONT_SIZE : i32 = 40;
+
+const BACK_BUTTON_PADDING : i32 = 10;
+const BACK_BUTTON_SIZE : i32 = 50;
+
+const SAVE_BUTTON_WIDTH : i32 = 200;
+const SAVE_BUTTON_HEIGHT : i32 = 55;
+const SAVE_BUTTON_BOTTOM_PADDING : i32 = 20;
+
+const ROWS_TOP : i32 = 100;
+const ROW_HEIGHT : i32 = 55;
+const ROW_WIDTH : i32 = 620;
+// Start of the value column, the label of the row is drawn to the left of it
+const VALUE_OFFSET : i32 = 280;
+const CONTROL_HEIGHT : i32 = 40;
+const CHECKBOX_SIZE : i32 = 30;
+const SLIDER_WIDTH : i32 = 200;
+// Space between the animation speed slider and the name of the speed drawn after it
+const SLIDER_TEXT_SPACING : i32 = 15;
+
+// Labels of the rows, in the order their widgets are laid out
+const ROWS : [&str ; 7] = ["Theme", "Orientation", "Coordinates", "Animation speed", "Confirm moves", "Player name", "Server address"];
+const SPEED_ROW : usize = 3;
+
+const MESSAGE_FONT_SIZE : i32 = 20;
+
+/// Preferences kept between runs, changes are only used and written to the config file once saved
+pub struct Settings {
+    settings: SharedSettings,
+    message: Option<String>,
+    widgets: Widgets<Self>,
+    theme_id: usize,
+    orientation_id: usize,
+    coordinates_id: usize,
+    speed_id: usize,
+    confirm_id: usize,
+    name_id: usize,
+    address_id: usize,
+    title_font: WeakFont,
+    font: WeakFont,
+    message_font: WeakFont,
+}
+
+impl Scene for Settings {
+    fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) {
+        let (window_width, window_height) = (draw_handler.get_screen_width(), draw_handler.get_screen_height());
+        let left = rows_left(window_width);
+        draw_text(draw_handler, &self.title_font, "Settings", left, ROWS_TOP - TITLE_FONT_SIZE - BACK_BUTTON_PADDING * 2, TITLE_FONT_SIZE, RayColor::WHITE);
+
+        for (row, name) in ROWS.iter().enumerate() {
+            draw_text(draw_handler, &self.font, name, left, row_top(row) + (ROW_HEIGHT - FONT_SIZE) / 2, FONT_SIZE, RayColor::WHITE);
+        }
+
+        let speed = self.animation_speed().name();
+        let x = left + VALUE_OFFSET + SLIDER_WIDTH + SLIDER_TEXT_SPACING;
+        draw_text(draw_handler, &self.font, speed, x, row_top(SPEED_ROW) + (ROW_HEIGHT - FONT_SIZE) / 2, FONT_SIZE, RayColor::WHITE);
+
+        if let Some(message) = &self.message {
+            let (width, _) = measure(&self.message_font, message, MESSAGE_FONT_SIZE);
+            let y = save_button_top(window_height) - MESSAGE_FONT_SIZE - BACK_BUTTON_PADDING;
+            draw_text(draw_handler, &self.message_font, message, (window_width - width) / 2, y, MESSAGE_FONT_SIZE, RayColor::get_color(0xf5_57_42_ff));
+        }
+
+        self.widgets.draw(draw_handler);
+    }
+
+    fn update(&mut self, rl: &mut RaylibHandle, _: &RaylibThread) -> SceneInitType {
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            return SceneInitType::Start;
+        }
+
+        let callbacks = self.widgets.update(rl);
+        dispatch(self, rl, callbacks)
+    }
+}
+
+impl Settings {
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources, settings: &SharedSettings) -> Self {
+        let current = settings.get();
+        let mut widgets = Widgets::new();
+
+        let mut back_button = Button::new(resources.ui_font(rl, thread, TITLE_FONT_SIZE));
+        back_button.set_text("<", TITLE_FONT_SIZE);
+        let back_button_id = widgets.add(back_button);
+        widgets.on(back_button_id, Event::Clicked, |_, _| SceneInitType::Start);
+
+        let themes : Vec<String> = resources.themes().iter().map(|theme| theme.name.clone()).collect();
+        let mut theme = Dropdown::new(resources.ui_font(rl, thread, FONT_SIZE), FONT_SIZE);
+        let selected = themes.iter().position(|name| name == &current.theme).unwrap_or(0);
+        theme.set_options(themes);
+        theme.set_selected(selected);
+        let theme_id = widgets.add(theme);
+
+        let mut orientation = Dropdown::new(resources.ui_font(rl, thread, FONT_SIZE), FONT_SIZE);
+        orientation.set_options(Orientation::ALL.iter().map(|o| o.name().to_string()).collect());
+        orientation.set_selected(Orientation::ALL.iter().position(|&o| o == current.orientation).unwrap_or(0));
+        let orientation_id = widgets.add(orientation);
+
+        let mut coordinates = Checkbox::new(resources.ui_font(rl, thread, FONT_SIZE));
+        coordinates.set_text("Show around the board", FONT_SIZE);
+        coordinates.set_checked(current.show_coordinates);
+        let coordinates_id = widgets.add(coordinates);
+
+        // the slider picks an index into AnimationSpeed::ALL
+        let mut speed = Slider::new(0.0, (AnimationSpeed::ALL.len() - 1) as f32, 1.0);
+        speed.set_value(AnimationSpeed::ALL.iter().position(|&s| s == current.animation_speed).unwrap_or(0) as f32);
+        let speed_id = widgets.add(speed);
+
+        let mut confirm = Checkbox::new(resources.ui_font(rl, thread, FONT_SIZE));
+        confirm.set_text("Ask before playing", FONT_SIZE);
+        confirm.set_checked(current.confirm_moves);
+        let confirm_id = widgets.add(confirm);
+
+        let inputs : [(&String, &'static str, Option<fn(&str) -> bool>) ; 2] = [
+            (&current.player_name, "Your name", None),
+            (&current.server_address, "host:port", Some(valid_address)),
+        ];
+        let [name_id, address_id] = inputs.map(|(text, placeholder, validator)| {
+            let mut input = Input::init(resources.ui_font(rl, thread, FONT_SIZE), FONT_SIZE);
+            input.set_text(text);
+            input.set_placeholder(placeholder);
+            if let Some(validator) = validator {
+                input.set_validator(validator);
+            }
+            input.set_bg_color(RayColor::get_color(0xff_ff_ff_0a));
+            input.set_selected_bg_color(RayColor::get_color(0xff_ff_ff_1a));
+            input.set_border_color(RayColor::get_color(0xff_ff_ff_a0));
+            widgets.add(input)
+        });
+
+        let mut save_button = Button::new(resources.ui_font(rl, thread, TITLE_FONT_SIZE));
+        save_button.set_text("Save", TITLE_FONT_SIZE);
+        let save_button_id = widgets.add(save_button);
+        widgets.on(save_button_id, Event::Clicked, |scene: &mut Self, _| scene.save());
+
+        // the label of a row is drawn left of the value column, where its widget goes
+        let row = |control: Layout| Layout::row([Layout::space().width(Size::Fixed(VALUE_OFFSET)), control]).height(Size::Fixed(ROW_HEIGHT));
+        let control = |id| Layout::widget(id).height(Size::Fixed(CONTROL_HEIGHT)).anchor(Align::Start, Align::Center);
+        let checkbox = |id| Layout::widget(id).height(Size::Fixed(CHECKBOX_SIZE)).anchor(Align::Start, Align::Center);
+        let rows = [
+            row(control(theme_id)),
+            row(control(orientation_id)),
+            row(checkbox(coordinates_id)),
+            row(control(speed_id).width(Size::Fixed(SLIDER_WIDTH))),
+            row(checkbox(confirm_id)),
+            row(control(name_id)),
+            row(control(address_id)),
+        ];
+
+        widgets.set_layout(rl, Layout::overlay([
+            Layout::widget(back_button_id).size(BACK_BUTTON_SIZE, BACK_BUTTON_SIZE).margin(Edges::all(BACK_BUTTON_PADDING)),
+            Layout::column(rows).width(Size::Fixed(ROW_WIDTH)).anchor(Align::Center, Align::Start).margin(Edges::new(0, ROWS_TOP, 0, 0)),
+            Layout::widget(save_button_id).size(SAVE_BUTTON_WIDTH, SAVE_BUTTON_HEIGHT).anchor(Align::Center, Align::End).margin(Edges::new(0, 0, 0, SAVE_BUTTON_BOTTOM_PADDING)),
+        ]));
+
+        Settings {
+            settings: settings.clone(),
+            message: None,
+            widgets,
+            theme_id,
+            orientation_id,
+            coordinates_id,
+            speed_id,
+            confirm_id,
+            name_id,
+            address_id,
+            title_font: resources.ui_font(rl, thread, TITLE_FONT_SIZE),
+            font: resources.ui_font(rl, thread, FONT_SIZE),
+            message_font: resources.ui_font(rl, thread, MESSAGE_FONT_SIZE),
+        }
+    }
+
+    fn animation_speed(&self) -> AnimationSpeed {
+        let index = self.widgets.slider(self.speed_id).map_or(0, |slider| slider.get_value().round() as usize);
+        AnimationSpeed::ALL[index.min(AnimationSpeed::ALL.len() - 1)]
+    }
+
+    fn save(&mut self) -> SceneInitType {
+        let mut draft = self.settings.get();
+        if let Some(theme) = self.widgets.dropdown(self.theme_id).and_then(|dropdown| dropdown.selected_option()) {
+            draft.theme = theme.clone();
+        }
+        if let Some(dropdown) = self.widgets.dropdown(self.orientation_id) {
+            draft.orientation = Orientation::ALL[dropdown.get_selected()];
+        }
+        draft.show_coordinates = self.widgets.checkbox(self.coordinates_id).is_some_and(|checkbox| checkbox.is_checked());
+        draft.animation_speed = self.animation_speed();
+        draft.confirm_moves = self.widgets.checkbox(self.confirm_id).is_some_and(|checkbox| checkbox.is_checked());
+        draft.player_name = self.widgets.text(self.name_id).trim().to_string();
+        draft.server_address = self.widgets.text(self.address_id).trim().to_string();
+        if !valid_address(&draft.server_address) {
+            self.message = Some("The server address should look like host:port".to_string());
+            return SceneInitType::None;
+        }
+
+        match self.settings.save(draft) {
+            Ok(_) => SceneInitType::Start,
+            Err(e) => {
+                self.message = Some(format!("Could not save settings: {}", e));
+                SceneInitType::None
+            }
+        }
+    }
+}
+
+fn rows_left(window_width: i32) -> i32 {
+    (window_width - ROW_WIDTH) / 2
+}
+
+fn row_top(row: usize) -> i32 {
+    ROWS_TOP + row as i32 * ROW_HEIGHT
+}
+
+fn save_button_top(window_height: i32) -> i32 {
+    window_height - SAVE_BUTTON_HEIGHT - SAVE_BUTTON_BOTTOM_PADDING
+}
@@ -1,77 +1,68 @@
-use std::{borrow::BorrowMut, cell::RefCell, collections::HashMap, rc::Rc};
-
 use button::Button;
 use raylib::prelude::*;
 use raylib::color::Color as RayColor;
 
-use crate::{ui::{button, UIElement, UIElementTrait}, HEIGHT, WIDTH};
+use crate::{resources::Resources, ui::{button, layout::{Align, Edges, Layout, Size}, widgets::{dispatch, Event, Widgets}}};
 
 use super::{game::player::{local::LocalPlayer, PlayerTypes}, Scene, SceneInitType};
 
+// Buttons take 3 ninths of the width of the window
+const BUTTON_SHARE : (u32, u32) = (3, 9);
 const BUTTON_HEIGHT : i32 = 75;
+const BUTTON_STEP : i32 = 3 * BUTTON_HEIGHT / 2;
+const BUTTON_COUNT : usize = 6;
+// Space kept above and below the buttons when the window is too short for the full step
+const BUTTON_MARGIN : i32 = 20;
 const FONT_SIZE : i32 = 45;
 
 pub struct Start {
-    elements: [UIElement; 2],
-    actions: HashMap<usize, Box<dyn Fn(&Self) -> SceneInitType>>
+    widgets: Widgets<Self>,
 }
 
 impl Scene for Start {
     fn draw(&mut self, draw_handler: &mut RaylibDrawHandle) {
-        for element in &self.elements {
-            element.draw(draw_handler);
-        }
+        self.widgets.draw(draw_handler);
     }
 
     fn update(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) -> SceneInitType {
-        for element in &mut self.elements {
-            if element.update(rl) {
-                let id = element.get_id();
-                if let Some(action) = self.actions.get(&id) {
-                    return action(self);
-                }
-            }
-        }
-
-        SceneInitType::None
+        let callbacks = self.widgets.update(rl);
+        dispatch(self, rl, callbacks)
     }
 }
 
 impl Start {
-    pub fn init(rl: &mut RaylibHandle) -> Start {
+    pub fn init(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources) -> Start {
         let normal_color = RayColor::get_color(0xff_ff_ff_50);
         let hovered_color = RayColor::get_color(0xff_ff_ff_70);
 
-        let mut local_play_button = Button::new(rl.get_font_default());
-        let local_play_button_id = local_play_button.get_id();
-        local_play_button.center_horizontal_width(3 * WIDTH / 9);
-        local_play_button.set_top((HEIGHT - (3 * BUTTON_HEIGHT / 2)) / 2);
-        local_play_button.set_height(BUTTON_HEIGHT);
-        local_play_button.set_text("Local", FONT_SIZE);
-        local_play_button.set_color(normal_color);
-        local_play_button.set_hovered_color(hovered_color);
+        let menu : [(&'static str, fn() -> SceneInitType) ; BUTTON_COUNT] = [
+            ("Local", || SceneInitType::Game([PlayerTypes::Local(LocalPlayer::init()), PlayerTypes::Local(LocalPlayer::init())])),
+            ("Remote", || SceneInitType::RemoteConn(None)),
+            ("Chess960", || SceneInitType::Chess960),
+            ("Load game", || SceneInitType::LoadGame),
+            ("Editor", || SceneInitType::Editor(None)),
+            ("Settings", || SceneInitType::Settings),
+        ];
 
-        let mut mult_play_button = Button::new(rl.get_font_default());
-        let mult_play_button_id = mult_play_button.get_id();
-        mult_play_button.center_horizontal_width(3 * WIDTH / 9);
-        mult_play_button.set_top((HEIGHT + (3 * BUTTON_HEIGHT / 2)) / 2);
-        mult_play_button.set_height(BUTTON_HEIGHT);
-        mult_play_button.set_text("Remote", FONT_SIZE);
-        mult_play_button.set_color(normal_color);
-        mult_play_button.set_hovered_color(hovered_color);
+        let mut widgets = Widgets::new();
+        let mut buttons = vec![];
+        for (text, next) in menu {
+            let mut button = Button::new(resources.ui_font(rl, thread, FONT_SIZE));
+            button.set_text(text, FONT_SIZE);
+            button.set_color(normal_color);
+            button.set_hovered_color(hovered_color);
 
-        let mut start = Start {
-            elements: [UIElement::Button(local_play_button), UIElement::Button(mult_play_button)],
-            actions: HashMap::new()
-        };
-
-        start.add_action(local_play_button_id, Box::new(|_: &Self|  SceneInitType::Game([PlayerTypes::Local(LocalPlayer::init()), PlayerTypes::Local(LocalPlayer::init())])));
-        start.add_action(mult_play_button_id, Box::new(|_: &Self| SceneInitType::RemoteConn));
+            let id = widgets.add(button);
+            widgets.on(id, Event::Clicked, move |_, _| next());
+            buttons.push(Layout::centered(Layout::widget(id), BUTTON_SHARE.0, BUTTON_SHARE.1).height(Size::Fixed(BUTTON_HEIGHT)));
+        }
 
-        start
-    }
+        // the buttons are stacked around the middle of the window, closer together when it is short
+        widgets.set_layout(rl, Layout::column(buttons)
+            .spacing(BUTTON_STEP - BUTTON_HEIGHT)
+            .padding(Edges::symmetric(0, BUTTON_MARGIN))
+            .justify(Align::Center));
 
-    fn add_action(&mut self, id: usize, action: Box<dyn Fn(&Self) -> SceneInitType>) {
-        self.actions.insert(id, action);
+        Start { widgets }
     }
 }
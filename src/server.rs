@@ -1,9 +1,20 @@
-use std::{io::{self, Read}, net::{TcpListener, TcpStream}, ops::Index, sync::{atomic::AtomicBool, Arc}, thread, time::{Duration, SystemTime, UNIX_EPOCH}};
+use std::{io::{self, Read}, net::{TcpListener, TcpStream}, ops::Index, sync::{atomic::AtomicBool, mpsc::{self, Receiver, Sender}, Arc}, thread, time::{Duration, SystemTime, UNIX_EPOCH}};
 
 mod client;
-use chess_networking::Ack;
+use chess_networking::{Ack, GameState};
 use client::ServerClient;
-use viktoe_chess::{board::Turn, prelude::BoardPosition, ChessGame};
+use viktoe_chess::{board::Turn, piece::{Color, Piece}, prelude::BoardPosition, ChessGame};
+
+use crate::chess::{
+    chess960::castling_slots,
+    draw_rules::DrawRules,
+    fen::{chess_from_fen, parse_fen, placement_to_fen, START_FEN},
+    from_network_promotion,
+    index_to_position,
+    notation::square_name,
+    piece_at,
+    promotion_piece,
+};
 
 pub enum ServerState {
     GameInitiation,
@@ -21,13 +32,23 @@ pub struct Server {
     white: Option<ServerClient>,
     black: Option<ServerClient>,
     turn: Turn,
+    // position asked for by the first player that sent one, the standard position if none did
+    fen: Option<String>,
+    // positions and half-move clock, to end the game at fivefold repetition or after 75 moves
+    draw_rules: DrawRules,
+    castling: String,
+    halfmove: u32,
 
-    clients: Vec<ServerClient>
+    clients: Vec<ServerClient>,
+    // errors are reported to the window, which shows them to the player
+    errors: Sender<String>,
 }
 
 impl Server {
-    pub fn init(port: u16) -> std::io::Result<(Self, Arc<AtomicBool>)> {
+    /// The server, the flag that keeps it running and the errors it reports while it runs
+    pub fn init(port: u16) -> std::io::Result<(Self, Arc<AtomicBool>, Receiver<String>)> {
         let running = Arc::new(AtomicBool::new(true));
+        let (errors, received) = mpsc::channel();
         let listener = TcpListener::bind(format!("127.0.0.1:{}", port))?;
         listener.set_nonblocking(true).unwrap();
 
@@ -41,26 +62,36 @@ impl Server {
             white: None,
             black: None,
             turn: Turn::White,
+            fen: None,
+            draw_rules: DrawRules::default(),
+            castling: String::new(),
+            halfmove: 0,
 
-            clients: vec![]
-        }, running))
+            clients: vec![],
+            errors,
+        }, running, received))
     }
 
     pub fn check_for_client(&mut self) -> Option<ServerClient> {
         if let Ok((stream, addr)) = self.listener.accept() {
             stream.set_nonblocking(true).unwrap();
-            Some(ServerClient::new(stream, addr))
+            Some(ServerClient::new(stream, addr, self.errors.clone()))
         } else {
             None
         }
     }
 
     fn initiation(&mut self) {
-        if let (Some(white), Some(black)) = (&mut self.white, &mut self.black) {
+        if self.white.is_some() && self.black.is_some() {
             self.clients.clear();
             self.state = ServerState::Playing;
-            white.send_start();
-            black.send_start();
+            self.start_draw_rules();
+            if let (Some(white), Some(black)) = (&mut self.white, &mut self.black) {
+                white.set_fen(self.fen.clone());
+                black.set_fen(self.fen.clone());
+                white.send_start();
+                black.send_start();
+            }
             return;
         }
 
@@ -76,7 +107,23 @@ impl Server {
             if let Some(mut start) = client.read_start() {
                 let white_occupied = self.white.is_some();
                 let black_occupied  = self.black.is_some();
-                start.fen = None;
+
+                if let (None, Some(fen)) = (&self.fen, start.fen.take()) {
+                    match chess_from_fen(&fen) {
+                        Ok(chess) => {
+                            self.chess = chess;
+                            self.fen = Some(fen);
+                            self.turn = match self.chess.get_player_turn() {
+                                Turn::White => Turn::White,
+                                Turn::Black => Turn::Black,
+                            };
+                        },
+                        Err(e) => {
+                            let _ = self.errors.send(format!("Could not start from the position asked for: {}", e));
+                        },
+                    }
+                }
+
                 start.time = None;
                 start.inc = None;
 
@@ -96,10 +143,23 @@ impl Server {
             let from = BoardPosition::try_from(move_packet.from).unwrap();
             let to = BoardPosition::try_from(move_packet.to).unwrap();
 
+            let move_slots = (slot_of(move_packet.from), slot_of(move_packet.to));
+            let resets_clock = self.resets_clock(&from, &to);
+
             match self.chess.move_piece(&from, &to) {
                 Ok(state) => {
-                    self.get_current_player().send_ack(Ack { ok: true, end_state: None });
+                    // clients send the piece with the move, queen for ones that leave it out
+                    if let viktoe_chess::board::GameState::Promotion(..) = state {
+                        let piece = move_packet.promotion.as_ref().map_or(4, from_network_promotion);
+                        let _ = self.chess.promote_pawn(promotion_piece(piece).unwrap());
+                    }
+
                     self.update_turn();
+                    let drawn = self.record_position(move_slots, resets_clock);
+
+                    // the player who moved is no longer the one to move. The opponent only ever
+                    // gets moves, both clients end a forced draw on their own with the same rules
+                    self.get_waiting_player().send_ack(Ack { ok: true, end_state: drawn.then_some(GameState::Draw) });
                     self.get_current_player().send_move(&move_packet);
                     self.get_current_player().read_ack();
                 },
@@ -130,8 +190,6 @@ impl Server {
             
             thread::sleep(Duration::from_millis(1));
         }
-
-        println!("Server stopped");
     }
 
     fn get_current_player(&mut self) -> &mut ServerClient {
@@ -141,6 +199,69 @@ impl Server {
         }
     }
 
+    fn get_waiting_player(&mut self) -> &mut ServerClient {
+        match self.turn {
+            Turn::White => self.black.as_mut().expect("Black player should exist"),
+            Turn::Black => self.white.as_mut().expect("White player should exist")
+        }
+    }
+
+    fn start_draw_rules(&mut self) {
+        let fen = self.fen.clone().unwrap_or(START_FEN.to_string());
+        let fields = parse_fen(&fen).or_else(|_| parse_fen(START_FEN)).unwrap();
+
+        self.castling = fields.castling.replace('-', "");
+        self.halfmove = fields.halfmove;
+        self.draw_rules = DrawRules::new(&fen);
+    }
+
+    /// A pawn move or a capture, which has to be checked before the move is played
+    fn resets_clock(&self, from: &BoardPosition, to: &BoardPosition) -> bool {
+        match (self.chess.get_square(from), self.chess.get_square(to)) {
+            (Some(Color::White(Piece::Pawn { .. }) | Color::Black(Piece::Pawn { .. })), _) => true,
+            (Some(Color::White(_)), Some(Color::Black(_))) | (Some(Color::Black(_)), Some(Color::White(_))) => true,
+            _ => false,
+        }
+    }
+
+    /// Count the position after a move for the draw rules, ending the game once it is drawn
+    ///
+    /// Return if the game was drawn by the move
+    fn record_position(&mut self, (from, to): (i32, i32), resets_clock: bool) -> bool {
+        let start = self.fen.clone().unwrap_or(START_FEN.to_string());
+        let placement = start.split_whitespace().next().unwrap_or_default();
+        for (right, king, rook) in castling_slots(placement) {
+            if [king, rook].contains(&from) || [king, rook].contains(&to) {
+                self.castling.retain(|c| c != right);
+            }
+        }
+
+        self.halfmove = if resets_clock { 0 } else { self.halfmove + 1 };
+
+        let pawn_moved = matches!(self.chess.get_square(&index_to_position(to)), Some(Color::White(Piece::Pawn { .. }) | Color::Black(Piece::Pawn { .. })));
+        let en_passant = if pawn_moved && (to - from).abs() == 16 {
+            square_name((from + to) / 2)
+        } else {
+            "-".to_string()
+        };
+
+        let placement = placement_to_fen(|index| piece_at(&self.chess, index));
+        let side = match self.turn {
+            Turn::White => "w",
+            Turn::Black => "b",
+        };
+        let castling = if self.castling.is_empty() { "-" } else { &self.castling };
+        self.draw_rules.record(&format!("{} {} {} {} {} 1", placement, side, castling, en_passant, self.halfmove));
+
+        // the player who moved is told along with the answer to the move
+        if self.draw_rules.forced().is_some() {
+            self.state = ServerState::Ended;
+            return true;
+        }
+
+        false
+    }
+
     fn update_turn(&mut self) {
         self.turn = match self.chess.get_player_turn() {
             Turn::White => Turn::White,
@@ -148,3 +269,7 @@ impl Server {
         }
     }
 }
+
+fn slot_of((x, y): (u8, u8)) -> i32 {
+    y as i32 * 8 + x as i32
+}
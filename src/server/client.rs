@@ -1,4 +1,4 @@
-use std::{fmt::{Debug}, io::{ErrorKind, Read, Write, Error}, mem, net::{SocketAddr, TcpStream}};
+use std::{fmt::{Debug}, io::{ErrorKind, Read, Write, Error}, mem, net::{SocketAddr, TcpStream}, sync::mpsc::Sender};
 
 use chess_networking::{Ack, Move, Start};
 
@@ -6,14 +6,17 @@ pub struct ServerClient {
     stream: TcpStream,
     addr: SocketAddr,
     opts: Option<Start>,
+    // errors are reported to the window, which shows them to the player
+    errors: Sender<String>,
 }
 
 impl ServerClient {
-    pub fn new(stream: TcpStream, addr: SocketAddr) -> Self {
+    pub fn new(stream: TcpStream, addr: SocketAddr, errors: Sender<String>) -> Self {
         Self {
             stream,
             addr,
-            opts: None
+            opts: None,
+            errors,
         }
     }
 
@@ -23,6 +26,7 @@ impl ServerClient {
             stream: self.stream.try_clone().unwrap(),
             addr: self.addr,
             opts: Some(opts),
+            errors: self.errors.clone(),
         }
     }
 
@@ -36,6 +40,13 @@ impl ServerClient {
         }
     }
 
+    /// Position the game starts from, sent along with the start
+    pub fn set_fen(&mut self, fen: Option<String>) {
+        if let Some(start) = &mut self.opts {
+            start.fen = fen;
+        }
+    }
+
     pub fn is_established(&self) -> bool {
         self.opts.is_some()
     }
@@ -47,12 +58,10 @@ impl ServerClient {
         let _ = self.stream.read(&mut buf);
         
         match chess_networking::Start::try_from(buf.as_slice()) {
-            Ok(start) => {
-                println!("Successfully read start");
-                Some(start)
-            },
+            Ok(start) => Some(start),
+            Err(rmp_serde::decode::Error::Syntax(_)) => None,
             Err(e) => {
-                println!("Server Error: {}", e);
+                self.report(format!("Could not read the start from {}: {}", self.addr, e));
                 None
             }
         }
@@ -61,7 +70,7 @@ impl ServerClient {
     pub fn send_start(&mut self) {
         if let Some(start) = &self.opts {
             if let Err(e) = send(&mut self.stream, start) {
-                println!("\nServer Error sending start: {}\n", e);
+                self.report(format!("Could not send the start to {}: {}", self.addr, e));
             }
         }
     }
@@ -72,13 +81,10 @@ impl ServerClient {
         let _ = self.stream.read(&mut buf);
 
         match chess_networking::Move::try_from(buf.as_slice()) {
-            Ok(_move) => {
-                println!("Successfully read move");
-                Some(_move)
-            },
+            Ok(_move) => Some(_move),
             Err(rmp_serde::decode::Error::Syntax(_)) => { None },
             Err(e) => {
-                println!("Error: {}", e);
+                self.report(format!("Could not read the move from {}: {}", self.addr, e));
                 None
             }
         }
@@ -86,7 +92,7 @@ impl ServerClient {
 
     pub fn send_move(&mut self, _move: &Move) {
         if let Err(e) = send(&mut self.stream, _move) {
-            println!("\nServer Error sending move: {}\n", e);
+            self.report(format!("Could not send the move to {}: {}", self.addr, e));
         }
     }
 
@@ -100,7 +106,7 @@ impl ServerClient {
             },
             Err(rmp_serde::decode::Error::Syntax(_)) => { None },
             Err(e) => {
-                println!("Error: {}", e);
+                self.report(format!("Could not read the answer from {}: {}", self.addr, e));
                 None
             }
         }
@@ -108,9 +114,14 @@ impl ServerClient {
 
     pub fn send_ack(&mut self, ack: Ack) {
         if let Err(e) = send(&mut self.stream, &ack) {
-            println!("\nServer Error sending ack: {}\n", e);
+            self.report(format!("Could not send the answer to {}: {}", self.addr, e));
         }
     }
+
+    fn report(&self, error: String) {
+        // nobody is left to tell once the window is closed
+        let _ = self.errors.send(error);
+    }
 }
 
 pub fn send<T>(stream: &mut TcpStream, value: &T) -> Result<(), Error>
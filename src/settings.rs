@@ -0,0 +1,188 @@
+use std::{cell::RefCell, env, fs, io, path::PathBuf, rc::Rc};
+
+use crate::ui::toast;
+
+const SETTINGS_DIRECTORY : &str = "chess";
+const SETTINGS_FILE : &str = "settings.cfg";
+
+// Duration in seconds of the turn bar animation at normal speed
+const ANIMATION_SECONDS : f32 = 0.2;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Orientation {
+    WhiteAtBottom,
+    BlackAtBottom,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AnimationSpeed {
+    Off,
+    Slow,
+    Normal,
+    Fast,
+}
+
+/// Preferences changed in the settings scene and kept between runs. They are used by games,
+/// the connection scene and scene transitions, the widgets keep their own colors and timings
+#[derive(Clone)]
+pub struct Settings {
+    pub theme: String,
+    /// Orientation of local games, remote games keep the player's own pieces at the bottom
+    pub orientation: Orientation,
+    pub show_coordinates: bool,
+    pub animation_speed: AnimationSpeed,
+    pub player_name: String,
+    pub server_address: String,
+    /// Wait for the move to be confirmed before it is played
+    pub confirm_moves: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: "Classic".to_string(),
+            orientation: Orientation::WhiteAtBottom,
+            show_coordinates: true,
+            animation_speed: AnimationSpeed::Normal,
+            player_name: "Player".to_string(),
+            server_address: "127.0.0.1:5000".to_string(),
+            confirm_moves: false,
+        }
+    }
+}
+
+impl Orientation {
+    pub const ALL : [Orientation ; 2] = [Orientation::WhiteAtBottom, Orientation::BlackAtBottom];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Orientation::WhiteAtBottom => "White at bottom",
+            Orientation::BlackAtBottom => "Black at bottom",
+        }
+    }
+}
+
+impl AnimationSpeed {
+    pub const ALL : [AnimationSpeed ; 4] = [AnimationSpeed::Off, AnimationSpeed::Slow, AnimationSpeed::Normal, AnimationSpeed::Fast];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnimationSpeed::Off => "Off",
+            AnimationSpeed::Slow => "Slow",
+            AnimationSpeed::Normal => "Normal",
+            AnimationSpeed::Fast => "Fast",
+        }
+    }
+
+    /// Seconds an animation of normal length takes at this speed, none when animations are off
+    pub fn seconds(&self) -> f32 {
+        let factor = match self {
+            AnimationSpeed::Off => 0.0,
+            AnimationSpeed::Slow => 2.0,
+            AnimationSpeed::Normal => 1.0,
+            AnimationSpeed::Fast => 0.5,
+        };
+
+        ANIMATION_SECONDS * factor
+    }
+}
+
+impl Settings {
+    fn to_text(&self) -> String {
+        [
+            ("theme", self.theme.clone()),
+            ("orientation", self.orientation.name().to_string()),
+            ("coordinates", self.show_coordinates.to_string()),
+            ("animation", self.animation_speed.name().to_string()),
+            ("name", self.player_name.clone()),
+            ("server", self.server_address.clone()),
+            ("confirm_moves", self.confirm_moves.to_string()),
+        ]
+        .iter()
+        .map(|(key, value)| format!("{} = {}\n", key, value))
+        .collect()
+    }
+
+    /// Read `key = value` lines, keys that are missing or can't be read keep their default
+    fn from_text(text: &str) -> Self {
+        let mut settings = Settings::default();
+
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            let value = value.trim();
+            match key.trim() {
+                "theme" => settings.theme = value.to_string(),
+                "orientation" => if let Some(orientation) = Orientation::ALL.iter().find(|o| o.name() == value) {
+                    settings.orientation = *orientation;
+                },
+                "coordinates" => settings.show_coordinates = value.parse().unwrap_or(settings.show_coordinates),
+                "animation" => if let Some(speed) = AnimationSpeed::ALL.iter().find(|s| s.name() == value) {
+                    settings.animation_speed = *speed;
+                },
+                "name" => settings.player_name = value.to_string(),
+                "server" => settings.server_address = value.to_string(),
+                "confirm_moves" => settings.confirm_moves = value.parse().unwrap_or(settings.confirm_moves),
+                key => toast::warning(format!("Unknown setting in {}: {}", SETTINGS_FILE, key)),
+            }
+        }
+
+        settings
+    }
+}
+
+/// Settings in use for the whole run. Loaded in `main` and handed to the scenes, cloning it
+/// gives another handle to the same settings
+#[derive(Clone, Default)]
+pub struct SharedSettings(Rc<RefCell<Settings>>);
+
+impl SharedSettings {
+    /// Load the settings from the config file, falling back to the defaults if there is none
+    pub fn load() -> Self {
+        let settings = match config_path().map(fs::read_to_string) {
+            Some(Ok(text)) => Settings::from_text(&text),
+            _ => Settings::default(),
+        };
+
+        Self(Rc::new(RefCell::new(settings)))
+    }
+
+    /// The settings currently in use
+    pub fn get(&self) -> Settings {
+        self.0.borrow().clone()
+    }
+
+    /// Use `settings` from now on, without writing them to the config file
+    pub fn set(&self, settings: Settings) {
+        *self.0.borrow_mut() = settings;
+    }
+
+    /// Use `settings` from now on and write them to the config file
+    pub fn save(&self, settings: Settings) -> io::Result<PathBuf> {
+        let path = config_path().ok_or(io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&path, settings.to_text())?;
+        self.set(settings);
+
+        Ok(path)
+    }
+}
+
+/// `settings.cfg` in the user's config directory
+fn config_path() -> Option<PathBuf> {
+    let directory = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library").join("Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }?;
+
+    Some(directory.join(SETTINGS_DIRECTORY).join(SETTINGS_FILE))
+}
@@ -1,13 +1,70 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
 use button::Button;
+use checkbox::Checkbox;
+use dropdown::Dropdown;
 use input::Input;
 use label::Label;
+use list::ListView;
+use slider::Slider;
 
 use crate::scenes::{Scene, SceneInitType};
 
 pub mod animate;
 pub mod button;
+pub mod checkbox;
+pub mod dropdown;
 pub mod input;
 pub mod label;
+pub mod layout;
+pub mod list;
+pub mod slider;
+pub mod text;
+pub mod toast;
+pub mod widgets;
+
+/// Background of clickable widgets, and of them under the mouse
+pub const NORMAL_COLOR : u32 = 0xff_ff_ff_50;
+pub const HOVERED_COLOR : u32 = 0xff_ff_ff_70;
+
+/// Outline of the widget that has the keyboard focus
+const FOCUS_COLOR : u32 = 0xf0_c0_40_ff;
+const FOCUS_THICKNESS : i32 = 3;
+
+/// Id for a new widget, shared by every kind of widget so that ids never collide
+pub fn next_id() -> usize {
+    static NEXT_ID : AtomicUsize = AtomicUsize::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Size of `text` as `draw_text` draws it. Letters of the default font are spaced by a tenth of
+/// the font size, which is never smaller than 10
+pub fn measure(font: &WeakFont, text: &str, font_size: i32) -> (i32, i32) {
+    let font_size = font_size.max(10);
+    let size = font.measure_text(text, font_size as f32, (font_size / 10) as f32);
+    (size.x as i32, size.y as i32)
+}
+
+/// Draw `text` in `font` with the spacing `measure` expects, so that widgets line up what they
+/// measured with what is drawn
+pub fn draw_text(draw_handler: &mut impl RaylibDraw, font: &WeakFont, text: &str, x: i32, y: i32, font_size: i32, color: RayColor) {
+    let font_size = font_size.max(10);
+    draw_handler.draw_text_ex(font, text, Vector2::new(x as f32, y as f32), font_size as f32, (font_size / 10) as f32, color);
+}
+
+/// Outline drawn around the widget that has the keyboard focus, just outside of its bounds
+pub fn draw_focus(draw_handler: &mut RaylibDrawHandle, left: i32, top: i32, width: i32, height: i32) {
+    let bounds = Rectangle::new(
+        (left - FOCUS_THICKNESS) as f32,
+        (top - FOCUS_THICKNESS) as f32,
+        (width + 2 * FOCUS_THICKNESS) as f32,
+        (height + 2 * FOCUS_THICKNESS) as f32,
+    );
+    draw_handler.draw_rectangle_lines_ex(bounds, FOCUS_THICKNESS as f32, RayColor::get_color(FOCUS_COLOR));
+}
 
 pub trait UIElementTrait {
     // Return true if element action has been activated
@@ -18,33 +75,139 @@ pub trait UIElementTrait {
 
 pub enum UIElement {
     Button(Button),
+    Checkbox(Checkbox),
+    Dropdown(Dropdown),
     Input(Input),
-    Label(Label)
+    Label(Label),
+    List(ListView),
+    Slider(Slider),
+}
+
+impl UIElement {
+    /// Move the element, used by layouts to follow the window when it is resized
+    pub fn set_bounds(&mut self, left: i32, top: i32, width: i32, height: i32) {
+        match self {
+            UIElement::Button(button) => button.set_bounds(left, top, width, height),
+            UIElement::Checkbox(checkbox) => checkbox.set_bounds(left, top, width, height),
+            UIElement::Dropdown(dropdown) => dropdown.set_bounds(left, top, width, height),
+            UIElement::Input(input) => input.set_bounds(left, top, width, height),
+            UIElement::Label(label) => label.set_bounds(left, top, width, height),
+            UIElement::List(list) => list.set_bounds(left, top, width, height),
+            UIElement::Slider(slider) => slider.set_bounds(left, top, width, height),
+        }
+    }
+
+    /// Whether Tab can move the keyboard focus to the element
+    pub fn is_focusable(&self) -> bool {
+        match self {
+            UIElement::Button(button) => button.is_enabled(),
+            UIElement::Checkbox(checkbox) => checkbox.is_enabled(),
+            UIElement::Dropdown(dropdown) => dropdown.is_enabled(),
+            UIElement::Input(input) => input.is_enabled(),
+            UIElement::List(list) => list.is_enabled(),
+            UIElement::Slider(slider) => slider.is_enabled(),
+            UIElement::Label(_) => false,
+        }
+    }
+
+    /// Give or take the keyboard focus, the focused element is activated by Enter or Space
+    pub fn set_focused(&mut self, value: bool) {
+        match self {
+            UIElement::Button(button) => button.set_focused(value),
+            UIElement::Checkbox(checkbox) => checkbox.set_focused(value),
+            UIElement::Dropdown(dropdown) => dropdown.set_focused(value),
+            UIElement::Input(input) => input.set_focused(value),
+            UIElement::List(list) => list.set_focused(value),
+            UIElement::Slider(slider) => slider.set_focused(value),
+            UIElement::Label(_) => {},
+        }
+    }
+
+    /// True for an open dropdown, which covers the widgets under it and takes the mouse from them
+    pub fn captures_input(&self) -> bool {
+        match self {
+            UIElement::Dropdown(dropdown) => dropdown.is_open(),
+            _ => false,
+        }
+    }
 }
 
 impl UIElementTrait for UIElement {
     fn update(&mut self, rl: &mut raylib::RaylibHandle) -> bool {
         match self {
             UIElement::Button(button) => button.update(rl),
+            UIElement::Checkbox(checkbox) => checkbox.update(rl),
+            UIElement::Dropdown(dropdown) => dropdown.update(rl),
             UIElement::Input(input) => input.update(rl),
             UIElement::Label(label) => label.update(rl),
+            UIElement::List(list) => list.update(rl),
+            UIElement::Slider(slider) => slider.update(rl),
         }
     }
 
     fn draw(&self, draw_handler: &mut raylib::prelude::RaylibDrawHandle) {
         match self {
             UIElement::Button(button) => button.draw(draw_handler),
+            UIElement::Checkbox(checkbox) => checkbox.draw(draw_handler),
+            UIElement::Dropdown(dropdown) => dropdown.draw(draw_handler),
             UIElement::Input(input) => input.draw(draw_handler),
             UIElement::Label(label) => label.draw(draw_handler),
+            UIElement::List(list) => list.draw(draw_handler),
+            UIElement::Slider(slider) => slider.draw(draw_handler),
         }
     }
 
     fn get_id(&self) -> usize {
         match self {
             UIElement::Button(button) => button.get_id(),
+            UIElement::Checkbox(checkbox) => checkbox.get_id(),
+            UIElement::Dropdown(dropdown) => dropdown.get_id(),
             UIElement::Input(input) => input.get_id(),
             UIElement::Label(label) => label.get_id(),
+            UIElement::List(list) => list.get_id(),
+            UIElement::Slider(slider) => slider.get_id(),
         }
     }
 }
 
+impl From<Button> for UIElement {
+    fn from(button: Button) -> Self {
+        UIElement::Button(button)
+    }
+}
+
+impl From<Input> for UIElement {
+    fn from(input: Input) -> Self {
+        UIElement::Input(input)
+    }
+}
+
+impl From<Label> for UIElement {
+    fn from(label: Label) -> Self {
+        UIElement::Label(label)
+    }
+}
+
+impl From<Checkbox> for UIElement {
+    fn from(checkbox: Checkbox) -> Self {
+        UIElement::Checkbox(checkbox)
+    }
+}
+
+impl From<Dropdown> for UIElement {
+    fn from(dropdown: Dropdown) -> Self {
+        UIElement::Dropdown(dropdown)
+    }
+}
+
+impl From<ListView> for UIElement {
+    fn from(list: ListView) -> Self {
+        UIElement::List(list)
+    }
+}
+
+impl From<Slider> for UIElement {
+    fn from(slider: Slider) -> Self {
+        UIElement::Slider(slider)
+    }
+}
@@ -1,44 +1,463 @@
-use std::f32::consts::PI;
+use std::{cell::Cell, f32::consts::PI, rc::Rc};
 
-pub enum Animations {
-    EaseInOutElastic,
-    EaseInOutBack,
-    EaseOutBounce,
-    EaseInOutCirc,
+use raylib::prelude::Vector2;
+use raylib::color::Color as RayColor;
+
+use super::layout::Rect;
+
+/// Curve mapping the time gone by, from 0 to 1, to the progress between the start and the end
+/// of a tween. Back and elastic curves go past the ends on their way
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Easing {
+    #[default]
+    Linear,
+    InSine, OutSine, InOutSine,
+    InQuad, OutQuad, InOutQuad,
+    InCubic, OutCubic, InOutCubic,
+    InQuart, OutQuart, InOutQuart,
+    InQuint, OutQuint, InOutQuint,
+    InExpo, OutExpo, InOutExpo,
+    InCirc, OutCirc, InOutCirc,
+    InBack, OutBack, InOutBack,
+    InElastic, OutElastic, InOutElastic,
+    InBounce, OutBounce, InOutBounce,
+}
+
+impl Easing {
+    pub fn apply(self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => x,
+            Easing::InSine => 1.0 - (x * PI / 2.0).cos(),
+            Easing::OutSine => (x * PI / 2.0).sin(),
+            Easing::InOutSine => -((PI * x).cos() - 1.0) / 2.0,
+            Easing::InQuad => ease_in_pow(x, 2),
+            Easing::OutQuad => ease_out_pow(x, 2),
+            Easing::InOutQuad => ease_in_out_pow(x, 2),
+            Easing::InCubic => ease_in_pow(x, 3),
+            Easing::OutCubic => ease_out_pow(x, 3),
+            Easing::InOutCubic => ease_in_out_pow(x, 3),
+            Easing::InQuart => ease_in_pow(x, 4),
+            Easing::OutQuart => ease_out_pow(x, 4),
+            Easing::InOutQuart => ease_in_out_pow(x, 4),
+            Easing::InQuint => ease_in_pow(x, 5),
+            Easing::OutQuint => ease_out_pow(x, 5),
+            Easing::InOutQuint => ease_in_out_pow(x, 5),
+            Easing::InExpo => if x == 0.0 { 0.0 } else { 2f32.powf(10.0 * x - 10.0) },
+            Easing::OutExpo => if x == 1.0 { 1.0 } else { 1.0 - 2f32.powf(-10.0 * x) },
+            Easing::InOutExpo => ease_in_out_expo(x),
+            Easing::InCirc => 1.0 - (1.0 - x * x).sqrt(),
+            Easing::OutCirc => (1.0 - (x - 1.0).powi(2)).sqrt(),
+            Easing::InOutCirc => ease_in_out_circ(x),
+            Easing::InBack => ease_in_back(x),
+            Easing::OutBack => 1.0 - ease_in_back(1.0 - x),
+            Easing::InOutBack => ease_in_out_back(x),
+            Easing::InElastic => ease_in_elastic(x),
+            Easing::OutElastic => 1.0 - ease_in_elastic(1.0 - x),
+            Easing::InOutElastic => ease_in_out_elastic(x),
+            Easing::InBounce => 1.0 - ease_out_bounce(1.0 - x),
+            Easing::OutBounce => ease_out_bounce(x),
+            Easing::InOutBounce => if x < 0.5 {
+                (1.0 - ease_out_bounce(1.0 - 2.0 * x)) / 2.0
+            } else {
+                (1.0 + ease_out_bounce(2.0 * x - 1.0)) / 2.0
+            },
+        }
+    }
+}
+
+/// Values a tween can move between, `t` is the eased progress which can go a little past 0 and 1
+pub trait Lerp: Copy {
+    fn lerp(self, to: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for i32 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        (self as f32).lerp(to as f32, t).round() as i32
+    }
+}
+
+impl Lerp for Vector2 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        Vector2::new(self.x.lerp(to.x, t), self.y.lerp(to.y, t))
+    }
+}
+
+impl Lerp for RayColor {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        let channel = |from: u8, to: u8| (from as f32).lerp(to as f32, t).round().clamp(0.0, 255.0) as u8;
+        RayColor::new(channel(self.r, to.r), channel(self.g, to.g), channel(self.b, to.b), channel(self.a, to.a))
+    }
 }
 
-pub struct Animation {
-    frames_elapsed: u32,
-    total_frames: u32,
-    animation: Animations,
+impl Lerp for Rect {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        Rect::new(self.left.lerp(to.left, t), self.top.lerp(to.top, t), self.width.lerp(to.width, t), self.height.lerp(to.height, t))
+    }
+}
+
+/// How many times an animation plays
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Repeat {
+    Once,
+    Times(u32),
+    Forever,
+}
+
+/// Anything that moves with the time, so that tweens, sequences and parallel groups can be
+/// nested in each other
+pub trait Animate {
+    /// Move `seconds` forward, returning the part of them left over once the animation finished
+    fn advance(&mut self, seconds: f32) -> f32;
+    fn is_finished(&self) -> bool;
+    /// Go back to the start, delay included
+    fn restart(&mut self);
 }
 
-impl Animation {
-    pub fn new(animation: Animations, total_frames: u32) -> Animation {
-        Animation {
-            frames_elapsed: 0,
-            total_frames,
-            animation
+/// Playback shared by tweens and groups: delay, repeats and the completion callback
+struct Playback {
+    delay: f32,
+    waited: f32,
+    repeat: Repeat,
+    // plays done so far, the one running included
+    plays: u32,
+    finished: bool,
+    on_complete: Option<Box<dyn FnMut()>>,
+}
+
+impl Playback {
+    fn new() -> Self {
+        Self { delay: 0.0, waited: 0.0, repeat: Repeat::Once, plays: 1, finished: false, on_complete: None }
+    }
+
+    /// Wait out the delay, returning the seconds left for the animation itself
+    fn wait(&mut self, seconds: f32) -> f32 {
+        let waiting = (self.delay - self.waited).clamp(0.0, seconds);
+        self.waited += waiting;
+        seconds - waiting
+    }
+
+    /// Called when a play reached its end, returns true if another one starts
+    fn play_again(&mut self) -> bool {
+        let again = match self.repeat {
+            Repeat::Once => false,
+            Repeat::Times(times) => self.plays < times,
+            Repeat::Forever => true,
+        };
+
+        if again {
+            self.plays += 1;
+        } else {
+            self.finished = true;
+            if let Some(on_complete) = &mut self.on_complete {
+                on_complete();
+            }
         }
+        again
+    }
+
+    fn restart(&mut self) {
+        (self.waited, self.plays, self.finished) = (0.0, 1, false);
+    }
+}
+
+/// Value moving from one end to the other over a number of seconds, measured with the frame time
+/// rather than by counting frames
+pub struct Tween<T: Lerp> {
+    from: T,
+    to: T,
+    seconds: f32,
+    easing: Easing,
+    // every other play goes back from the end to the start
+    yoyo: bool,
+    elapsed: f32,
+    playback: Playback,
+    output: Rc<Cell<T>>,
+}
+
+impl<T: Lerp + 'static> Tween<T> {
+    /// A tween of no length is at its end right away, which is how animations are turned off
+    pub fn new(from: T, to: T, seconds: f32) -> Self {
+        let mut tween = Self {
+            from, to,
+            seconds: seconds.max(0.0),
+            easing: Easing::Linear,
+            yoyo: false,
+            elapsed: 0.0,
+            playback: Playback::new(),
+            output: Rc::new(Cell::new(from)),
+        };
+        tween.update_output();
+        tween
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self.update_output();
+        self
+    }
+
+    /// Seconds to wait before the first play
+    pub fn delay(mut self, seconds: f32) -> Self {
+        self.playback.delay = seconds;
+        self
+    }
+
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.playback.repeat = repeat;
+        self
+    }
+
+    pub fn yoyo(mut self, value: bool) -> Self {
+        self.yoyo = value;
+        self
+    }
+
+    /// Called once when the last play ends
+    pub fn on_complete(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.playback.on_complete = Some(Box::new(callback));
+        self
+    }
+
+    /// Shared view of the value, which keeps following the tween once it is moved into a sequence
+    /// or parallel group
+    pub fn output(&self) -> Rc<Cell<T>> {
+        self.output.clone()
+    }
+
+    pub fn value(&self) -> T {
+        self.output.get()
+    }
+
+    /// Head for `to` from wherever the value is now, such as a hover colour that turns back
+    /// halfway through
+    pub fn retarget(&mut self, to: T) {
+        self.from = self.value();
+        self.to = to;
+        self.elapsed = 0.0;
+        self.playback.restart();
+        self.playback.waited = self.playback.delay;
+        self.update_output();
     }
 
-    pub fn next(&mut self) -> f32 {
-        let quotient = (self.frames_elapsed as f32) / (self.total_frames as f32);
+    fn update_output(&mut self) {
+        let progress = if self.seconds > 0.0 { self.elapsed / self.seconds } else { 1.0 };
+        let backwards = self.yoyo && self.playback.plays % 2 == 0;
+        let eased = self.easing.apply(if backwards { 1.0 - progress } else { progress });
+        self.output.set(self.from.lerp(self.to, eased));
+    }
+}
 
-        if self.frames_elapsed != self.total_frames {
-            self.frames_elapsed += 1;
+impl<T: Lerp + 'static> Animate for Tween<T> {
+    fn advance(&mut self, seconds: f32) -> f32 {
+        let mut seconds = self.playback.wait(seconds);
+        if self.playback.waited < self.playback.delay {
+            return 0.0;
         }
 
-        match self.animation {
-            Animations::EaseInOutElastic => ease_in_out_elastic(quotient),
-            Animations::EaseInOutBack => ease_in_out_back(quotient),
-            Animations::EaseOutBounce => ease_out_bounce(quotient),
-            Animations::EaseInOutCirc => ease_in_out_circ(quotient),
+        while !self.playback.finished {
+            let step = seconds.min(self.seconds - self.elapsed);
+            self.elapsed += step;
+            seconds -= step;
+            if self.elapsed < self.seconds || !self.playback.play_again() {
+                break;
+            }
+
+            self.elapsed = 0.0;
+            // a tween of no length would repeat forever within a single frame
+            if self.seconds == 0.0 || seconds <= 0.0 {
+                break;
+            }
         }
+
+        self.update_output();
+        if self.playback.finished { seconds } else { 0.0 }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.playback.finished
+    }
+
+    fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.playback.restart();
+        self.update_output();
+    }
+}
+
+/// Animations played one after the other
+pub struct Sequence {
+    steps: Vec<Box<dyn Animate>>,
+    current: usize,
+    playback: Playback,
+}
+
+/// Animations played at the same time, finished once the longest is
+pub struct Parallel {
+    members: Vec<Box<dyn Animate>>,
+    playback: Playback,
+}
+
+impl Sequence {
+    pub fn new(steps: Vec<Box<dyn Animate>>) -> Self {
+        Self { steps, current: 0, playback: Playback::new() }
     }
 
-    pub fn restart(&mut self) {
-        self.frames_elapsed = 0;
+    pub fn delay(mut self, seconds: f32) -> Self {
+        self.playback.delay = seconds;
+        self
+    }
+
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.playback.repeat = repeat;
+        self
+    }
+
+    pub fn on_complete(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.playback.on_complete = Some(Box::new(callback));
+        self
+    }
+}
+
+impl Animate for Sequence {
+    fn advance(&mut self, seconds: f32) -> f32 {
+        let mut seconds = self.playback.wait(seconds);
+        if self.playback.waited < self.playback.delay {
+            return 0.0;
+        }
+
+        let mut play_start = seconds;
+        while !self.playback.finished {
+            match self.steps.get_mut(self.current) {
+                Some(step) => {
+                    seconds = step.advance(seconds);
+                    if !step.is_finished() {
+                        return 0.0;
+                    }
+                    self.current += 1;
+                },
+                None => {
+                    if !self.playback.play_again() {
+                        break;
+                    }
+                    self.current = 0;
+                    self.steps.iter_mut().for_each(|step| step.restart());
+                    // steps that take no time would repeat forever within a single frame
+                    if seconds <= 0.0 || seconds == play_start {
+                        break;
+                    }
+                    play_start = seconds;
+                },
+            }
+        }
+        if self.playback.finished { seconds } else { 0.0 }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.playback.finished
+    }
+
+    fn restart(&mut self) {
+        self.current = 0;
+        self.steps.iter_mut().for_each(|step| step.restart());
+        self.playback.restart();
+    }
+}
+
+impl Parallel {
+    pub fn new(members: Vec<Box<dyn Animate>>) -> Self {
+        Self { members, playback: Playback::new() }
+    }
+
+    pub fn delay(mut self, seconds: f32) -> Self {
+        self.playback.delay = seconds;
+        self
+    }
+
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.playback.repeat = repeat;
+        self
+    }
+
+    pub fn on_complete(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.playback.on_complete = Some(Box::new(callback));
+        self
+    }
+}
+
+impl Animate for Parallel {
+    fn advance(&mut self, seconds: f32) -> f32 {
+        let seconds = self.playback.wait(seconds);
+        if self.playback.finished || self.playback.waited < self.playback.delay {
+            return seconds;
+        }
+
+        // the time left over is what the longest member didn't need
+        let left = self.members.iter_mut().map(|member| member.advance(seconds)).fold(seconds, f32::min);
+        if self.members.iter().all(|member| member.is_finished()) && self.playback.play_again() {
+            self.members.iter_mut().for_each(|member| member.restart());
+        }
+        if self.playback.finished { left } else { 0.0 }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.playback.finished
+    }
+
+    fn restart(&mut self) {
+        self.members.iter_mut().for_each(|member| member.restart());
+        self.playback.restart();
+    }
+}
+
+fn ease_in_pow(x: f32, power: i32) -> f32 {
+    x.powi(power)
+}
+
+fn ease_out_pow(x: f32, power: i32) -> f32 {
+    1.0 - (1.0 - x).powi(power)
+}
+
+fn ease_in_out_pow(x: f32, power: i32) -> f32 {
+    if x < 0.5 {
+        2f32.powi(power - 1) * x.powi(power)
+    } else {
+        1.0 - (-2.0 * x + 2.0).powi(power) / 2.0
+    }
+}
+
+fn ease_in_out_expo(x: f32) -> f32 {
+    if x == 0.0 {
+        0.0
+    } else if x == 1.0 {
+        1.0
+    } else if x < 0.5 {
+        2f32.powf(20.0 * x - 10.0) / 2.0
+    } else {
+        (2.0 - 2f32.powf(-20.0 * x + 10.0)) / 2.0
+    }
+}
+
+fn ease_in_back(x: f32) -> f32 {
+    const C1 : f32 = 1.70158;
+    const C3 : f32 = C1 + 1.0;
+
+    C3 * x.powi(3) - C1 * x.powi(2)
+}
+
+fn ease_in_elastic(x: f32) -> f32 {
+    if x == 0.0 {
+        0.0
+    } else if x == 1.0 {
+        1.0
+    } else {
+        -(2f32.powf(10.0 * x - 10.0) * ((x * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin())
     }
 }
 
@@ -48,35 +467,35 @@ fn ease_in_out_elastic(x: f32) -> f32 {
     } else if x == 1.0 {
         1.0
     } else if x < 0.5 {
-        -((2.0 as f32).powf(20.0 * x - 10.0) * ((20.0 * x - 11.125) * (2.0 * PI / 4.5)).sin()) / 2.0
+        -(2f32.powf(20.0 * x - 10.0) * ((20.0 * x - 11.125) * (2.0 * PI / 4.5)).sin()) / 2.0
     } else {
-        ((2.0 as f32).powf(-20.0 * x + 10.0) * ((20.0 * x - 11.125) * (2.0 * PI / 4.5)).sin()) / 2.0 + 1.0
+        (2f32.powf(-20.0 * x + 10.0) * ((20.0 * x - 11.125) * (2.0 * PI / 4.5)).sin()) / 2.0 + 1.0
     }
 }
 
 fn ease_in_out_back(x: f32) -> f32 {
-    const c1 : f32 = 1.70158;
-    const c2 : f32 = c1 * 1.525;
+    const C1 : f32 = 1.70158;
+    const C2 : f32 = C1 * 1.525;
 
     if x < 0.5 {
-        ((2.0 * x).powi(2) * ((c2 + 1.0) * 2.0 * x - c2)) / 2.0
+        ((2.0 * x).powi(2) * ((C2 + 1.0) * 2.0 * x - C2)) / 2.0
     } else {
-        ((2.0 * x - 2.0).powi(2) * ((c2 + 1.0) * (x * 2.0 - 2.0) + c2) + 2.0) / 2.0
+        ((2.0 * x - 2.0).powi(2) * ((C2 + 1.0) * (x * 2.0 - 2.0) + C2) + 2.0) / 2.0
     }
 }
 
 fn ease_out_bounce(x: f32) -> f32 {
-    const n : f32 = 7.5625;
-    const d : f32 = 2.75;
-
-    if x < 1.0 / d {
-        n * x * x
-    } else if x < 2.0 / d {
-        n * (x - 1.5 / d).powi(2) + 0.75
-    } else if x < 2.5 / d {
-        n * (x - 2.25 / d).powi(2) + 0.9375
+    const N : f32 = 7.5625;
+    const D : f32 = 2.75;
+
+    if x < 1.0 / D {
+        N * x * x
+    } else if x < 2.0 / D {
+        N * (x - 1.5 / D).powi(2) + 0.75
+    } else if x < 2.5 / D {
+        N * (x - 2.25 / D).powi(2) + 0.9375
     } else {
-        n * (x - 2.625 / d).powi(2) + 0.984375
+        N * (x - 2.625 / D).powi(2) + 0.984375
     }
 }
 
@@ -87,3 +506,119 @@ fn ease_in_out_circ(x: f32) -> f32 {
         ((1.0 - (-2.0 * x + 2.0).powi(2)).sqrt() + 1.0) / 2.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_EASINGS : [Easing ; 31] = [
+        Easing::Linear,
+        Easing::InSine, Easing::OutSine, Easing::InOutSine,
+        Easing::InQuad, Easing::OutQuad, Easing::InOutQuad,
+        Easing::InCubic, Easing::OutCubic, Easing::InOutCubic,
+        Easing::InQuart, Easing::OutQuart, Easing::InOutQuart,
+        Easing::InQuint, Easing::OutQuint, Easing::InOutQuint,
+        Easing::InExpo, Easing::OutExpo, Easing::InOutExpo,
+        Easing::InCirc, Easing::OutCirc, Easing::InOutCirc,
+        Easing::InBack, Easing::OutBack, Easing::InOutBack,
+        Easing::InElastic, Easing::OutElastic, Easing::InOutElastic,
+        Easing::InBounce, Easing::OutBounce, Easing::InOutBounce,
+    ];
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn easings_start_at_0_and_end_at_1() {
+        for easing in ALL_EASINGS {
+            assert_close(easing.apply(0.0), 0.0);
+            assert_close(easing.apply(1.0), 1.0);
+            // progress outside of the tween is clamped
+            assert_close(easing.apply(-1.0), 0.0);
+            assert_close(easing.apply(2.0), 1.0);
+        }
+    }
+
+    #[test]
+    fn waits_out_the_delay() {
+        let mut tween = Tween::new(0.0, 1.0, 1.0).delay(0.5);
+        assert_eq!(tween.advance(0.25), 0.0);
+        assert_close(tween.value(), 0.0);
+
+        tween.advance(0.5);
+        assert_close(tween.value(), 0.25);
+
+        tween.restart();
+        tween.advance(0.25);
+        assert_close(tween.value(), 0.0);
+    }
+
+    #[test]
+    fn repeats_the_given_number_of_times() {
+        let mut tween = Tween::new(0.0, 1.0, 1.0).repeat(Repeat::Times(3));
+        tween.advance(2.5);
+        assert!(!tween.is_finished());
+        assert_close(tween.value(), 0.5);
+
+        tween.advance(0.5);
+        assert!(tween.is_finished());
+        assert_close(tween.value(), 1.0);
+
+        let mut forever = Tween::new(0.0, 1.0, 1.0).repeat(Repeat::Forever);
+        forever.advance(100.25);
+        assert!(!forever.is_finished());
+        assert_close(forever.value(), 0.25);
+    }
+
+    #[test]
+    fn yoyo_goes_back_on_every_other_play() {
+        let mut tween = Tween::new(0.0, 1.0, 1.0).yoyo(true).repeat(Repeat::Times(2));
+        tween.advance(1.25);
+        assert_close(tween.value(), 0.75);
+
+        tween.advance(0.75);
+        assert!(tween.is_finished());
+        assert_close(tween.value(), 0.0);
+    }
+
+    #[test]
+    fn returns_the_time_left_over() {
+        let mut tween = Tween::new(0.0, 1.0, 1.0);
+        assert_close(tween.advance(1.5), 0.5);
+
+        // the time a step didn't need goes to the next one
+        let first = Tween::new(0.0, 1.0, 1.0);
+        let second = Tween::new(0.0, 1.0, 1.0);
+        let output = second.output();
+        let mut sequence = Sequence::new(vec![Box::new(first), Box::new(second)]);
+        assert_eq!(sequence.advance(1.5), 0.0);
+        assert_close(output.get(), 0.5);
+        assert_close(sequence.advance(1.0), 0.5);
+        assert!(sequence.is_finished());
+
+        // a group is done once its longest member is
+        let mut parallel = Parallel::new(vec![Box::new(Tween::new(0.0, 1.0, 1.0)), Box::new(Tween::new(0.0, 1.0, 2.0))]);
+        assert_eq!(parallel.advance(1.5), 0.0);
+        assert_close(parallel.advance(1.0), 0.5);
+    }
+
+    #[test]
+    fn calls_back_once_when_complete() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let mut tween = Tween::new(0.0, 1.0, 1.0).repeat(Repeat::Times(2)).on_complete(move || counter.set(counter.get() + 1));
+        tween.advance(1.5);
+        assert_eq!(calls.get(), 0);
+        tween.advance(1.0);
+        tween.advance(1.0);
+        assert_eq!(calls.get(), 1);
+
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let mut sequence = Sequence::new(vec![Box::new(Tween::new(0.0, 1.0, 0.5))]).repeat(Repeat::Times(2)).on_complete(move || counter.set(counter.get() + 1));
+        sequence.advance(5.0);
+        sequence.advance(5.0);
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -1,12 +1,13 @@
-use std::sync::atomic::AtomicUsize;
 
-use ffi::MeasureText;
 use raylib::prelude::*;
 use raylib::color::Color as RayColor;
 
-use crate::{scenes::{SceneInitType, SceneStorage}, HEIGHT, WIDTH};
+use super::{animate::{Animate, Easing, Tween}, draw_focus, layout::{Align, Edges, Rect}, next_id, text::TextBlock, UIElementTrait, HOVERED_COLOR, NORMAL_COLOR};
 
-use super::{UIElement, UIElementTrait};
+// Space kept between the sides of the button and text aligned to them or wrapped
+const TEXT_PADDING : i32 = 10;
+// Time the background takes to change colour when the mouse enters or leaves
+const HOVER_SECONDS : f32 = 0.12;
 
 pub struct Button
 {
@@ -18,17 +19,14 @@ pub struct Button
     top: i32,
     bottom: i32,
 
-    text: &'static str,
-    font: WeakFont,
-    text_font_size: i32,
-    text_width: i32,
-    text_height: i32,
-    text_x: i32,
-    text_y: i32,
+    text: TextBlock,
 
     is_hovering: bool,
+    is_focused: bool,
     normal_color: RayColor,
     hovered_color: RayColor,
+    // background as it fades between the two colours
+    color: Tween<RayColor>,
 }
 
 impl UIElementTrait for Button {
@@ -37,8 +35,15 @@ impl UIElementTrait for Button {
             return false;
         }
 
-        self.is_hovering = (self.left..self.right).contains(&rl.get_mouse_x()) && (self.top..self.bottom).contains(&rl.get_mouse_y());
-        return self.is_hovering && rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+        let hovering = (self.left..self.right).contains(&rl.get_mouse_x()) && (self.top..self.bottom).contains(&rl.get_mouse_y());
+        if hovering != self.is_hovering {
+            self.is_hovering = hovering;
+            self.color.retarget(self.target_color());
+        }
+        self.color.advance(rl.get_frame_time());
+
+        let activated = self.is_focused && (rl.is_key_pressed(KeyboardKey::KEY_ENTER) || rl.is_key_pressed(KeyboardKey::KEY_SPACE));
+        return (self.is_hovering && rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT)) || activated;
     }
 
     fn draw(&self, draw_handler: &mut RaylibDrawHandle) {
@@ -46,12 +51,12 @@ impl UIElementTrait for Button {
             return;
         }
 
-        draw_handler.draw_rectangle(self.left, self.top, self.right - self.left, self.bottom - self.top, 
-            if self.is_hovering { self.hovered_color }
-            else { self.normal_color }
-        );
+        draw_handler.draw_rectangle(self.left, self.top, self.right - self.left, self.bottom - self.top, self.color.value());
+        if self.is_focused {
+            draw_focus(draw_handler, self.left, self.top, self.right - self.left, self.bottom - self.top);
+        }
 
-        draw_handler.draw_text(self.text, self.text_x, self.text_y, self.text_font_size, RayColor::BLACK);
+        self.text.draw(draw_handler, RayColor::BLACK);
     }
 
     fn get_id(&self) -> usize {
@@ -61,15 +66,16 @@ impl UIElementTrait for Button {
 
 impl Button {
     pub fn new(font: WeakFont) -> Self {
-        static NEXT_ID : AtomicUsize = AtomicUsize::new(0);
         Self {
-            id: NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            id: next_id(),
             enabled: true,
             left: 0, right: 0, top: 0, bottom: 0,
-            text: "", font, text_font_size: 0, text_width: 0, text_height: 0, text_x: 0, text_y: 0,
+            text: TextBlock::new(font, 0),
             is_hovering: false,
-            normal_color: RayColor::get_color(0xff_ff_ff_50),
-            hovered_color: RayColor::get_color(0xff_ff_ff_70),
+            is_focused: false,
+            normal_color: RayColor::get_color(NORMAL_COLOR),
+            hovered_color: RayColor::get_color(HOVERED_COLOR),
+            color: still(RayColor::get_color(NORMAL_COLOR)),
         }
     }
 
@@ -77,76 +83,56 @@ impl Button {
         self.enabled = value;
     }
 
-    pub fn set_left(&mut self, value: i32) {
-        self.left = value;
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
     }
 
-    pub fn set_right(&mut self, value: i32) {
-        self.right = value;
+    pub fn set_focused(&mut self, value: bool) {
+        self.is_focused = value;
     }
 
-    pub fn set_top(&mut self, value: i32) {
-        self.top = value;
+    /// Move the button, keeping the text in place inside it. Set by the layout of the scene
+    pub fn set_bounds(&mut self, left: i32, top: i32, width: i32, height: i32) {
+        (self.left, self.top, self.right, self.bottom) = (left, top, left + width, top + height);
+        self.text.set_bounds(Rect::new(left, top, width, height).shrink(Edges::symmetric(TEXT_PADDING, 0)));
     }
 
-    pub fn set_bottom(&mut self, value: i32) {
-        self.bottom = value;
+    /// Change the text and its size at any time, it is measured and placed again
+    pub fn set_text(&mut self, text: impl Into<String>, font_size: i32) {
+        self.text.set_text(text);
+        self.text.set_font_size(font_size);
     }
 
-    pub fn set_width(&mut self, width: i32) {
-        if self.left != 0 {
-            self.right = self.left + width;
-        } else if self.right != 0 {
-            self.left = self.right - width;
-        } else {
-            panic!("Must set left or right of button to be able to determine constraints based on width");
-        }
-    }
-
-    pub fn set_height(&mut self, height: i32) {
-        if self.top != 0 {
-            self.bottom = self.top + height;
-        } else if self.bottom != 0 {
-            self.top = self.bottom - height;
-        } else {
-            panic!("Must set top or bottom of button to be able to determine constraints based on height");
-        }
+    pub fn get_text(&self) -> &str {
+        self.text.get_text()
     }
 
-    pub fn center_horizontal_width(&mut self, width: i32) {
-        self.left = (WIDTH - width) / 2;
-        self.right = (WIDTH + width) / 2;
+    /// Break the text onto several lines where it is wider than the button
+    pub fn set_wrap(&mut self, value: bool) {
+        self.text.set_wrap(value);
     }
 
-    pub fn center_vertical_height(&mut self, height: i32) {
-        self.top = (HEIGHT - height) / 2;
-        self.bottom = (HEIGHT - height) / 2;
-    }
-
-    pub fn set_text(&mut self, text: &'static str, font_size: i32) {
-        self.text = text;
-        self.text_font_size = font_size;
-        
-        let width = self.right - self.left;
-        let height = self.bottom - self.top;
-        // Some made up spacing that seems to center the text correctly?
-        let text_size = self.font.measure_text(text, font_size as f32, 3.5);
-
-        self.text_width = text_size.x as i32;
-        self.text_height = text_size.y as i32;
- 
-        assert!(self.text_width < width);
-        assert!(self.text_height < height);
-
-        self.text_x = self.left + (width - self.text_width) / 2;
-        self.text_y = self.top + (height - self.text_height) / 2;
+    /// Where the text goes in the button, centered by default
+    pub fn set_align(&mut self, horizontal: Align, vertical: Align) {
+        self.text.set_align(horizontal, vertical);
     }
 
     pub fn set_color(&mut self, color: RayColor) {
         self.normal_color = color;
+        self.color = still(self.target_color());
     }
 
     pub fn set_hovered_color(&mut self, color: RayColor) {
         self.hovered_color = color;
+        self.color = still(self.target_color());
+    }
+
+    fn target_color(&self) -> RayColor {
+        if self.is_hovering { self.hovered_color } else { self.normal_color }
     }
 }
+
+/// Background resting on `color` until the mouse moves over or off the button
+fn still(color: RayColor) -> Tween<RayColor> {
+    Tween::new(color, color, HOVER_SECONDS).easing(Easing::OutQuad)
+}
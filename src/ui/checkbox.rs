@@ -0,0 +1,114 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use super::{draw_focus, draw_text, layout::Rect, measure, next_id, UIElementTrait, HOVERED_COLOR, NORMAL_COLOR};
+
+// Space between the box and its label
+const LABEL_SPACING : i32 = 10;
+
+/// Box that is ticked and unticked by clicking it or its label
+pub struct Checkbox {
+    id: usize,
+    enabled: bool,
+    bounds: Rect,
+
+    text: &'static str,
+    font: WeakFont,
+    text_font_size: i32,
+
+    checked: bool,
+    is_hovering: bool,
+    is_focused: bool,
+    normal_color: RayColor,
+    hovered_color: RayColor,
+}
+
+impl UIElementTrait for Checkbox {
+    /// True when the box was ticked or unticked
+    fn update(&mut self, rl: &mut RaylibHandle) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        self.is_hovering = self.bounds.contains(rl.get_mouse_x(), rl.get_mouse_y());
+        let activated = self.is_focused && (rl.is_key_pressed(KeyboardKey::KEY_ENTER) || rl.is_key_pressed(KeyboardKey::KEY_SPACE));
+        if (self.is_hovering && rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT)) || activated {
+            self.checked = !self.checked;
+            return true;
+        }
+        false
+    }
+
+    fn draw(&self, draw_handler: &mut RaylibDrawHandle) {
+        if !self.enabled {
+            return;
+        }
+
+        // the box is as tall as the bounds, with the label to its right
+        let size = self.bounds.height;
+        let color = if self.is_hovering { self.hovered_color } else { self.normal_color };
+        draw_handler.draw_rectangle(self.bounds.left, self.bounds.top, size, size, color);
+        if self.is_focused {
+            draw_focus(draw_handler, self.bounds.left, self.bounds.top, size, size);
+        }
+        if self.checked {
+            let inset = size / 4;
+            draw_handler.draw_rectangle(self.bounds.left + inset, self.bounds.top + inset, size - 2 * inset, size - 2 * inset, RayColor::BLACK);
+        }
+
+        let (_, text_height) = measure(&self.font, self.text, self.text_font_size);
+        let y = self.bounds.top + (size - text_height) / 2;
+        draw_text(draw_handler, &self.font, self.text, self.bounds.left + size + LABEL_SPACING, y, self.text_font_size, RayColor::WHITE);
+    }
+
+    fn get_id(&self) -> usize {
+        self.id
+    }
+}
+
+impl Checkbox {
+    pub fn new(font: WeakFont) -> Self {
+        Self {
+            id: next_id(),
+            enabled: true,
+            bounds: Rect::default(),
+            text: "", font, text_font_size: 0,
+            checked: false,
+            is_hovering: false,
+            is_focused: false,
+            normal_color: RayColor::get_color(NORMAL_COLOR),
+            hovered_color: RayColor::get_color(HOVERED_COLOR),
+        }
+    }
+
+    pub fn set_enabled(&mut self, value: bool) {
+        self.enabled = value;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_focused(&mut self, value: bool) {
+        self.is_focused = value;
+    }
+
+    /// Label drawn right of the box
+    pub fn set_text(&mut self, text: &'static str, font_size: i32) {
+        self.text = text;
+        self.text_font_size = font_size;
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn set_checked(&mut self, value: bool) {
+        self.checked = value;
+    }
+
+    /// Set by the layout of the scene
+    pub fn set_bounds(&mut self, left: i32, top: i32, width: i32, height: i32) {
+        self.bounds = Rect::new(left, top, width, height);
+    }
+}
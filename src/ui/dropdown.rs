@@ -0,0 +1,180 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use super::{draw_focus, draw_text, layout::Rect, measure, next_id, UIElementTrait, HOVERED_COLOR, NORMAL_COLOR};
+
+const TEXT_PADDING : i32 = 10;
+const LIST_COLOR : u32 = 0x20_20_20_f0;
+
+/// Button showing the selected option, which opens the list of options below it when clicked
+pub struct Dropdown {
+    id: usize,
+    enabled: bool,
+    bounds: Rect,
+
+    options: Vec<String>,
+    selected: usize,
+    font: WeakFont,
+    text_font_size: i32,
+
+    open: bool,
+    is_hovering: bool,
+    is_focused: bool,
+    // option under the mouse while the list is open
+    hovered: Option<usize>,
+    normal_color: RayColor,
+    hovered_color: RayColor,
+}
+
+impl UIElementTrait for Dropdown {
+    /// True when another option was picked
+    fn update(&mut self, rl: &mut RaylibHandle) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let (x, y) = (rl.get_mouse_x(), rl.get_mouse_y());
+        self.is_hovering = self.bounds.contains(x, y);
+        self.hovered = (0..self.options.len()).find(|&index| self.open && self.option_bounds(index).contains(x, y));
+
+        // with the focus, Enter or Space opens and closes the list and the arrows pick the option
+        // before or after the selected one
+        if self.is_focused {
+            if rl.is_key_pressed(KeyboardKey::KEY_ENTER) || rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
+                self.open = !self.open;
+            } else if self.open && rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+                self.open = false;
+            }
+
+            let selected = self.selected;
+            if rl.is_key_pressed(KeyboardKey::KEY_UP) {
+                self.selected = self.selected.saturating_sub(1);
+            } else if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+                self.selected = (self.selected + 1).min(self.options.len().saturating_sub(1));
+            }
+            if self.selected != selected {
+                return true;
+            }
+        }
+
+        if !rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            return false;
+        }
+
+        // any click closes an open list, picking the option it lands on
+        if self.open {
+            self.open = false;
+            if let Some(index) = self.hovered.filter(|&index| index != self.selected) {
+                self.selected = index;
+                return true;
+            }
+        } else if self.is_hovering {
+            self.open = true;
+        }
+        false
+    }
+
+    fn draw(&self, draw_handler: &mut RaylibDrawHandle) {
+        if !self.enabled {
+            return;
+        }
+
+        let color = if self.is_hovering || self.open { self.hovered_color } else { self.normal_color };
+        draw_handler.draw_rectangle(self.bounds.left, self.bounds.top, self.bounds.width, self.bounds.height, color);
+        if self.is_focused {
+            draw_focus(draw_handler, self.bounds.left, self.bounds.top, self.bounds.width, self.bounds.height);
+        }
+        if let Some(option) = self.options.get(self.selected) {
+            self.draw_option(draw_handler, option, self.bounds, RayColor::BLACK);
+        }
+
+        let arrow = if self.open { "^" } else { "v" };
+        let (arrow_width, arrow_height) = measure(&self.font, arrow, self.text_font_size);
+        let arrow_x = self.bounds.right() - TEXT_PADDING - arrow_width;
+        draw_text(draw_handler, &self.font, arrow, arrow_x, self.bounds.top + (self.bounds.height - arrow_height) / 2, self.text_font_size, RayColor::BLACK);
+
+        if !self.open {
+            return;
+        }
+
+        for (index, option) in self.options.iter().enumerate() {
+            let bounds = self.option_bounds(index);
+            let color = if self.hovered == Some(index) || (self.hovered.is_none() && index == self.selected) { self.hovered_color } else { RayColor::get_color(LIST_COLOR) };
+            draw_handler.draw_rectangle(bounds.left, bounds.top, bounds.width, bounds.height, color);
+            self.draw_option(draw_handler, option, bounds, RayColor::WHITE);
+        }
+    }
+
+    fn get_id(&self) -> usize {
+        self.id
+    }
+}
+
+impl Dropdown {
+    pub fn new(font: WeakFont, text_font_size: i32) -> Self {
+        Self {
+            id: next_id(),
+            enabled: true,
+            bounds: Rect::default(),
+            options: vec![],
+            selected: 0,
+            font, text_font_size,
+            open: false,
+            is_hovering: false,
+            is_focused: false,
+            hovered: None,
+            normal_color: RayColor::get_color(NORMAL_COLOR),
+            hovered_color: RayColor::get_color(HOVERED_COLOR),
+        }
+    }
+
+    pub fn set_enabled(&mut self, value: bool) {
+        self.enabled = value;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_focused(&mut self, value: bool) {
+        self.is_focused = value;
+    }
+
+    pub fn set_options(&mut self, options: Vec<String>) {
+        self.options = options;
+        self.selected = self.selected.min(self.options.len().saturating_sub(1));
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, index: usize) {
+        if index < self.options.len() {
+            self.selected = index;
+        }
+    }
+
+    pub fn selected_option(&self) -> Option<&String> {
+        self.options.get(self.selected)
+    }
+
+    /// An open list covers the widgets below it, which don't get the clicks meant for it
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Set by the layout of the scene, the list opens below the bounds with rows of the same height
+    pub fn set_bounds(&mut self, left: i32, top: i32, width: i32, height: i32) {
+        self.bounds = Rect::new(left, top, width, height);
+    }
+
+    fn option_bounds(&self, index: usize) -> Rect {
+        Rect::new(self.bounds.left, self.bounds.bottom() + index as i32 * self.bounds.height, self.bounds.width, self.bounds.height)
+    }
+
+    fn draw_option(&self, draw_handler: &mut RaylibDrawHandle, option: &str, bounds: Rect, color: RayColor) {
+        let (_, text_height) = measure(&self.font, option, self.text_font_size);
+        draw_text(draw_handler, &self.font, option, bounds.left + TEXT_PADDING, bounds.top + (bounds.height - text_height) / 2, self.text_font_size, color);
+    }
+}
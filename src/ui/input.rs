@@ -1,11 +1,20 @@
-use std::sync::atomic::AtomicUsize;
 use raylib::prelude::*;
 use raylib::color::Color as RayColor;
 
-use crate::{HEIGHT, WIDTH};
+use super::{draw_focus, draw_text, measure, next_id, UIElementTrait};
 
-use super::UIElementTrait;
+// Space between the border and text that doesn't fit and has to scroll
+const TEXT_PADDING : i32 = 8;
+const CARET_WIDTH : i32 = 2;
+// Seconds a key is held before it repeats, and between repeats after that
+const REPEAT_DELAY : f32 = 0.4;
+const REPEAT_INTERVAL : f32 = 0.04;
 
+const SELECTION_COLOR : u32 = 0x34_8c_eb_80;
+const PLACEHOLDER_COLOR : u32 = 0xff_ff_ff_60;
+const INVALID_BORDER_COLOR : u32 = 0xf5_57_42_ff;
+
+/// Single line text editor with a caret, selection, clipboard and scrolling
 pub struct Input {
     id: usize,
     enabled: bool,
@@ -16,13 +25,24 @@ pub struct Input {
     bottom: i32,
 
     text: String,
+    placeholder: &'static str,
+    max_length: Option<usize>,
+    validator: Option<fn(&str) -> bool>,
     font: WeakFont,
     text_font_size: i32,
     text_width: i32,
-    text_height: i32,
     text_x: i32,
     text_y: i32,
 
+    // byte offsets into the text, the selection runs from the anchor to the caret
+    caret: usize,
+    selection_anchor: Option<usize>,
+    // pixels of the text scrolled out on the left when it is wider than the box
+    scroll: i32,
+    dragging: bool,
+    // key being held down and seconds until it fires again
+    repeat: Option<(KeyboardKey, f32)>,
+
     is_selected: bool,
 
     bg_color: RayColor,
@@ -36,28 +56,30 @@ impl UIElementTrait for Input {
             return false;
         }
 
-        if rl.is_mouse_button_pressed(raylib::ffi::MouseButton::MOUSE_BUTTON_LEFT) {
-            self.is_selected = (self.left..self.right).contains(&rl.get_mouse_x()) && (self.top..self.bottom).contains(&rl.get_mouse_y());
+        let (mouse_x, mouse_y) = (rl.get_mouse_x(), rl.get_mouse_y());
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.is_selected = (self.left..self.right).contains(&mouse_x) && (self.top..self.bottom).contains(&mouse_y);
+            self.dragging = self.is_selected;
+            if self.is_selected {
+                let shift = rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+                self.move_caret(self.offset_at(mouse_x), shift);
+            }
+        } else if self.dragging {
+            if rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+                self.move_caret(self.offset_at(mouse_x), true);
+            } else {
+                self.dragging = false;
+            }
         }
 
         if !self.is_selected {
             return false;
         }
 
-        while let Some(c) = rl.get_char_pressed() {
-            self.text.push(c);
-
-            if !self.text_fits() {
-                self.text.pop();
-            }
-        }
-
-        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
-            self.text.pop();
-        }
+        self.update_keys(rl);
         self.recalc_text();
 
-        rl.is_key_pressed(KeyboardKey::KEY_ENTER)
+        rl.is_key_pressed(KeyboardKey::KEY_ENTER) && self.is_valid()
     }
 
     fn draw(&self, draw_handler: &mut RaylibDrawHandle) {
@@ -67,9 +89,33 @@ impl UIElementTrait for Input {
 
         let width = self.right - self.left;
         let height = self.bottom - self.top;
+        let border_color = if self.is_valid() { self.border_color } else { RayColor::get_color(INVALID_BORDER_COLOR) };
         draw_handler.draw_rectangle(self.left, self.top, width, height, if self.is_selected { self.selected_bg_color} else {self.bg_color});
-        draw_handler.draw_rectangle_lines(self.left, self.top, width, height, self.border_color);
-        draw_handler.draw_text(self.text.as_str(), self.text_x, self.text_y, self.text_font_size, RayColor::WHITE);
+        draw_handler.draw_rectangle_lines(self.left, self.top, width, height, border_color);
+        if self.is_selected {
+            draw_focus(draw_handler, self.left, self.top, width, height);
+        }
+
+        // text scrolled out of the box is cut off at its border
+        let mut scissor = draw_handler.begin_scissor_mode(self.left + 1, self.top, width - 2, height);
+
+        if self.text.is_empty() {
+            let (placeholder_width, _) = measure(&self.font, self.placeholder, self.text_font_size);
+            let x = self.left + (width - placeholder_width) / 2;
+            draw_text(&mut scissor, &self.font, self.placeholder, x, self.text_y, self.text_font_size, RayColor::get_color(PLACEHOLDER_COLOR));
+        }
+
+        if let Some((start, end)) = self.selection() {
+            let (from, to) = (self.x_of(start), self.x_of(end));
+            scissor.draw_rectangle(from, self.text_y, to - from, self.text_font_size, RayColor::get_color(SELECTION_COLOR));
+        }
+
+        draw_text(&mut scissor, &self.font, self.text.as_str(), self.text_x, self.text_y, self.text_font_size, RayColor::WHITE);
+
+        if self.is_selected {
+            let x = if self.text.is_empty() { self.left + width / 2 } else { self.x_of(self.caret) };
+            scissor.draw_rectangle(x, self.text_y, CARET_WIDTH, self.text_font_size, RayColor::WHITE);
+        }
     }
 
     fn get_id(&self) -> usize { self.id }
@@ -77,12 +123,13 @@ impl UIElementTrait for Input {
 
 impl Input {
     pub fn init(font: WeakFont, text_font_size: i32) -> Self {
-        static NEXT_ID : AtomicUsize = AtomicUsize::new(0);
         Self {
-            id: NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            id: next_id(),
             enabled: true,
             left: 0, right: 0, top: 0, bottom: 0,
-            text: String::new(), font, text_font_size, text_height: 0, text_width: 0, text_y: 0, text_x: 0,
+            text: String::new(), placeholder: "", max_length: None, validator: None,
+            font, text_font_size, text_width: 0, text_y: 0, text_x: 0,
+            caret: 0, selection_anchor: None, scroll: 0, dragging: false, repeat: None,
             is_selected: false,
             bg_color: RayColor::default(),
             selected_bg_color: RayColor::default(),
@@ -94,54 +141,70 @@ impl Input {
         self.enabled = value;
     }
 
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Select the input as if it was clicked, or deselect it. The caret goes to the end of the text
+    pub fn set_focused(&mut self, value: bool) {
+        if value && !self.is_selected {
+            self.move_caret(self.text.len(), false);
+        }
+        self.is_selected = value;
+        self.dragging = false;
+    }
+
     pub fn get_text(&self) -> &String {
         &self.text
     }
 
-    pub fn set_left(&mut self, value: i32) {
-        self.left = value;
+    pub fn is_selected(&self) -> bool {
+        self.is_selected
     }
 
-    pub fn set_right(&mut self, value: i32) {
-        self.right = value;
+    /// Replace the text, cut to the maximum length, with the caret at its end
+    pub fn set_text(&mut self, text: &str) {
+        self.text = match self.max_length {
+            Some(max) => text.chars().take(max).collect(),
+            None => text.to_string(),
+        };
+        self.caret = self.text.len();
+        self.selection_anchor = None;
+        self.recalc_text();
     }
 
-    pub fn set_top(&mut self, value: i32) {
-        self.top = value;
+    pub fn clear(&mut self) {
+        self.set_text("");
     }
 
-    pub fn set_bottom(&mut self, value: i32) {
-        self.bottom = value;
+    /// Text shown greyed out while the input is empty
+    pub fn set_placeholder(&mut self, placeholder: &'static str) {
+        self.placeholder = placeholder;
     }
 
-    pub fn set_width(&mut self, width: i32) {
-        if self.left != 0 {
-            self.right = self.left + width;
-        } else if self.right != 0 {
-            self.left = self.right - width;
-        } else {
-            panic!("Must set left or right of button to be able to determine constraints based on width");
-        }
+    /// Most characters that can be typed or pasted in
+    pub fn set_max_length(&mut self, max_length: usize) {
+        self.max_length = Some(max_length);
+        let text = self.text.clone();
+        self.set_text(&text);
     }
 
-    pub fn set_height(&mut self, height: i32) {
-        if self.top != 0 {
-            self.bottom = self.top + height;
-        } else if self.bottom != 0 {
-            self.top = self.bottom - height;
-        } else {
-            panic!("Must set top or bottom of button to be able to determine constraints based on height");
-        }
+    /// Text that `validator` rejects is marked with a red border and can't be submitted with enter
+    pub fn set_validator(&mut self, validator: fn(&str) -> bool) {
+        self.validator = Some(validator);
     }
 
-    pub fn center_horizontal_width(&mut self, width: i32) {
-        self.left = (WIDTH - width) / 2;
-        self.right = (WIDTH + width) / 2;
+    pub fn is_valid(&self) -> bool {
+        match self.validator {
+            Some(validator) => validator(&self.text),
+            None => true,
+        }
     }
 
-    pub fn center_vertical_height(&mut self, height: i32) {
-        self.top = (HEIGHT - height) / 2;
-        self.bottom = (HEIGHT + height) / 2;
+    /// Move the input, keeping the text centered. Set by the layout of the scene
+    pub fn set_bounds(&mut self, left: i32, top: i32, width: i32, height: i32) {
+        (self.left, self.top, self.right, self.bottom) = (left, top, left + width, top + height);
+        self.recalc_text();
     }
 
     pub fn set_bg_color(&mut self, color: RayColor) {
@@ -156,24 +219,176 @@ impl Input {
         self.border_color = color;
     }
 
-    pub fn recalc_text(&mut self) { 
-        let width = self.right - self.left;
-        let height = self.bottom - self.top;
-        let text_sizes = self.measure_text();
+    /// Place the text in the box, centered when it fits and otherwise scrolled to keep the caret
+    /// in view
+    pub fn recalc_text(&mut self) {
+        self.text_width = measure(&self.font, &self.text, self.text_font_size).0;
+        self.text_y = self.top + (self.bottom - self.top - self.text_font_size) / 2;
+
+        let inner = self.right - self.left - 2 * TEXT_PADDING;
+        if self.text_width <= inner {
+            self.scroll = 0;
+            self.text_x = self.left + (self.right - self.left - self.text_width) / 2;
+            return;
+        }
+
+        let caret = self.width_of(self.caret);
+        if caret - self.scroll > inner {
+            self.scroll = caret - inner;
+        } else if caret < self.scroll {
+            self.scroll = caret;
+        }
+        self.scroll = self.scroll.clamp(0, self.text_width - inner);
+        self.text_x = self.left + TEXT_PADDING - self.scroll;
+    }
+
+    fn update_keys(&mut self, rl: &mut RaylibHandle) {
+        let ctrl = rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL) || rl.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL);
+        let shift = rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
 
-        assert!(self.text_width < width);
-        assert!(self.text_height < height);
+        // characters typed while control is held belong to the shortcuts
+        while let Some(c) = rl.get_char_pressed() {
+            if !ctrl && !c.is_control() {
+                self.insert(&c.to_string());
+            }
+        }
+
+        if ctrl && rl.is_key_pressed(KeyboardKey::KEY_A) {
+            self.selection_anchor = Some(0);
+            self.caret = self.text.len();
+        }
+        if ctrl && (rl.is_key_pressed(KeyboardKey::KEY_C) || rl.is_key_pressed(KeyboardKey::KEY_X)) {
+            if let Some((start, end)) = self.selection() {
+                let _ = rl.set_clipboard_text(&self.text[start..end]);
+                if rl.is_key_pressed(KeyboardKey::KEY_X) {
+                    self.delete_selection();
+                }
+            }
+        }
+        if ctrl && rl.is_key_pressed(KeyboardKey::KEY_V) {
+            if let Ok(text) = rl.get_clipboard_text() {
+                // a single line is kept of whatever was copied
+                let line = text.lines().next().unwrap_or_default().to_string();
+                self.insert(&line);
+            }
+        }
+
+        if self.key_fired(rl, KeyboardKey::KEY_BACKSPACE) && !self.delete_selection() {
+            let start = self.previous_offset(self.caret);
+            self.text.replace_range(start..self.caret, "");
+            self.caret = start;
+        }
+        if self.key_fired(rl, KeyboardKey::KEY_DELETE) && !self.delete_selection() {
+            let end = self.next_offset(self.caret);
+            self.text.replace_range(self.caret..end, "");
+        }
+
+        if self.key_fired(rl, KeyboardKey::KEY_LEFT) {
+            // without shift the caret goes to the start of the selection it leaves
+            match self.selection() {
+                Some((start, _)) if !shift => self.move_caret(start, false),
+                _ => self.move_caret(self.previous_offset(self.caret), shift),
+            }
+        }
+        if self.key_fired(rl, KeyboardKey::KEY_RIGHT) {
+            match self.selection() {
+                Some((_, end)) if !shift => self.move_caret(end, false),
+                _ => self.move_caret(self.next_offset(self.caret), shift),
+            }
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_HOME) {
+            self.move_caret(0, shift);
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_END) {
+            self.move_caret(self.text.len(), shift);
+        }
+    }
+
+    /// True the frame `key` is pressed, then again every repeat interval while it is held
+    fn key_fired(&mut self, rl: &RaylibHandle, key: KeyboardKey) -> bool {
+        if rl.is_key_pressed(key) {
+            self.repeat = Some((key, REPEAT_DELAY));
+            return true;
+        }
+
+        match &mut self.repeat {
+            Some((held, wait)) if *held == key => {
+                if !rl.is_key_down(key) {
+                    self.repeat = None;
+                    return false;
+                }
+
+                *wait -= rl.get_frame_time();
+                if *wait > 0.0 {
+                    return false;
+                }
+                *wait += REPEAT_INTERVAL;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Replace the selection with `text`, as much of it as the maximum length leaves room for
+    fn insert(&mut self, text: &str) {
+        self.delete_selection();
+
+        let room = self.max_length.map_or(usize::MAX, |max| max.saturating_sub(self.text.chars().count()));
+        let text : String = text.chars().filter(|c| !c.is_control()).take(room).collect();
+        self.text.insert_str(self.caret, &text);
+        self.caret += text.len();
+    }
+
+    /// Remove the selected text, returning false if nothing was selected
+    fn delete_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection() else {
+            return false;
+        };
+
+        self.text.replace_range(start..end, "");
+        self.caret = start;
+        self.selection_anchor = None;
+        true
+    }
+
+    /// Start and end of the selected text, if any
+    fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.selection_anchor?;
+        if anchor == self.caret {
+            return None;
+        }
+        Some((anchor.min(self.caret), anchor.max(self.caret)))
+    }
+
+    /// Put the caret at `offset`, extending the selection from where it was when `select` is set
+    fn move_caret(&mut self, offset: usize, select: bool) {
+        if select {
+            self.selection_anchor.get_or_insert(self.caret);
+        } else {
+            self.selection_anchor = None;
+        }
+        self.caret = offset;
+    }
+
+    fn previous_offset(&self, offset: usize) -> usize {
+        self.text[..offset].char_indices().next_back().map_or(0, |(index, _)| index)
+    }
+
+    fn next_offset(&self, offset: usize) -> usize {
+        self.text[offset..].chars().next().map_or(offset, |c| offset + c.len_utf8())
+    }
 
-        self.text_x = self.left + (width - text_sizes.0) / 2;
-        self.text_y = self.top + (height - text_sizes.1) / 2;
+    fn width_of(&self, offset: usize) -> i32 {
+        measure(&self.font, &self.text[..offset], self.text_font_size).0
     }
 
-    fn measure_text(&self) -> (i32, i32) {
-        let measure = self.font.measure_text(self.text.as_str(), self.text_font_size as f32, 3.5);
-        (measure.x as i32, measure.y as i32)
+    fn x_of(&self, offset: usize) -> i32 {
+        self.text_x + self.width_of(offset)
     }
 
-    fn text_fits(&self) -> bool {
-        self.text_width <= self.measure_text().0
+    /// Offset of the character boundary closest to the screen position `x`
+    fn offset_at(&self, x: i32) -> usize {
+        let boundaries = self.text.char_indices().map(|(index, _)| index).chain([self.text.len()]);
+        boundaries.min_by_key(|&offset| (self.x_of(offset) - x).abs()).unwrap_or(0)
     }
 }
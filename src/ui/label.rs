@@ -1,25 +1,20 @@
-use std::{ops::RangeBounds, sync::atomic::AtomicUsize, time::{Duration, SystemTime}};
+use std::time::{Duration, SystemTime};
 
 use raylib::prelude::*;
 use raylib::color::Color as RayColor;
 
-use crate::{HEIGHT, WIDTH};
-
-use super::UIElementTrait;
+use super::{layout::{Align, Rect}, next_id, text::TextBlock, UIElementTrait};
 
+/// Text that isn't clicked, either one set by the scene as it changes or several cycled through
+/// every timeout
 pub struct Label {
     id: usize,
     enabled: bool,
 
-    texts: Vec<&'static str>,
+    texts: Vec<String>,
     text_index: usize,
-    font: WeakFont,
-    font_size: i32,
+    text: TextBlock,
     color: RayColor,
-    width: i32,
-    height: i32,
-    x: i32,
-    y: i32,
 
     timeout: Duration,
     last_update: SystemTime,
@@ -27,19 +22,25 @@ pub struct Label {
 
 impl UIElementTrait for Label {
     fn update(&mut self, rl: &mut raylib::RaylibHandle) -> bool {
+        if self.texts.len() < 2 || self.timeout.is_zero() {
+            return false;
+        }
+
         if let Ok(duration) = self.last_update.elapsed() {
             if duration > self.timeout {
                 self.last_update += duration;
-                let (old_width, old_height) = (self.width, self.height);
                 self.next_text();
-                self.readjust(old_width, old_height);
             } 
         }
         false
     }
 
     fn draw(&self, draw_handler: &mut raylib::prelude::RaylibDrawHandle) {
-        draw_handler.draw_text(self.texts[self.text_index], self.x, self.y, self.font_size, self.color);
+        if !self.enabled {
+            return;
+        }
+
+        self.text.draw(draw_handler, self.color);
     }
 
     fn get_id(&self) -> usize {
@@ -49,19 +50,13 @@ impl UIElementTrait for Label {
 
 impl Label {
     pub fn new(font: WeakFont, font_size: i32) -> Self {
-        static NEXT_ID : AtomicUsize = AtomicUsize::new(0);
         Self {
-            id: NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            id: next_id(),
             enabled: true,
             texts: vec![],
             text_index: 0,
-            font,
-            font_size,
+            text: TextBlock::new(font, font_size),
             color: RayColor::default(),
-            width: 0,
-            height: 0,
-            x: 0,
-            y: 0,
             timeout: Duration::default(),
             last_update: SystemTime::now(),
         }
@@ -70,23 +65,29 @@ impl Label {
     pub fn set_index(&mut self, index: usize) {
         assert!((0..self.texts.len()).contains(&index));
         self.text_index = index;
-        let size = self.font.measure_text(self.texts[self.text_index], self.font_size as f32, 3.5);
-        (self.width, self.height) = (size.x as i32, size.y as i32);
+        self.text.set_text(self.texts[index].as_str());
     }
 
-    pub fn add_text(&mut self, text: &'static str) {
-        self.texts.push(text);
+    /// Add a text to cycle through, the first one added is shown until the timeout
+    pub fn add_text(&mut self, text: impl Into<String>) {
+        self.texts.push(text.into());
+        if self.texts.len() == 1 {
+            self.set_index(0);
+        }
     }
 
-    pub fn next_text(&mut self) {
-        self.text_index = (self.text_index + 1) % self.texts.len();
-        let size = self.font.measure_text(self.texts[self.text_index], self.font_size as f32, 3.5);
-        (self.width, self.height) = (size.x as i32, size.y as i32);
+    /// Show only `text` from now on, such as a message or a clock that changes every frame
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.texts = vec![text.into()];
+        self.set_index(0);
     }
 
-    pub fn readjust(&mut self, old_width: i32, old_height: i32) {
-        self.x += (old_width - self.width) / 2;
-        self.y += (old_height - self.height) / 2;
+    pub fn get_text(&self) -> &str {
+        self.text.get_text()
+    }
+
+    pub fn next_text(&mut self) {
+        self.set_index((self.text_index + 1) % self.texts.len());
     }
 
     pub fn set_enabled(&mut self, value: bool) {
@@ -97,25 +98,26 @@ impl Label {
         self.color = color;
     }
 
-    pub fn set_timeout(&mut self, value: Duration) {
-        self.timeout = value;
+    pub fn set_font_size(&mut self, font_size: i32) {
+        self.text.set_font_size(font_size);
     }
 
-    pub fn center_vertically(&mut self) {
-        assert!(self.height != 0);
-        self.y = (HEIGHT - self.height) / 2;
+    /// Break the text onto several lines where it is wider than the bounds
+    pub fn set_wrap(&mut self, value: bool) {
+        self.text.set_wrap(value);
     }
 
-    pub fn center_horizontally(&mut self) {
-        assert!(self.width != 0);
-        self.x = (WIDTH - self.width) / 2;
+    /// Where the text goes in the bounds, centered by default
+    pub fn set_align(&mut self, horizontal: Align, vertical: Align) {
+        self.text.set_align(horizontal, vertical);
     }
 
-    pub fn set_x(&mut self, value: i32) {
-        self.x = value;
+    pub fn set_timeout(&mut self, value: Duration) {
+        self.timeout = value;
     }
 
-    pub fn set_y(&mut self, value: i32) {
-        self.y = value;
+    /// Area the text is placed in. Set by the layout of the scene
+    pub fn set_bounds(&mut self, left: i32, top: i32, width: i32, height: i32) {
+        self.text.set_bounds(Rect::new(left, top, width, height));
     }
 }
@@ -0,0 +1,409 @@
+use raylib::RaylibHandle;
+
+use super::{UIElement, UIElementTrait};
+
+/// Area of the window in pixels
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    pub fn new(left: i32, top: i32, width: i32, height: i32) -> Self {
+        Self { left, top, width, height }
+    }
+
+    /// The whole window as it is this frame
+    pub fn window(rl: &RaylibHandle) -> Self {
+        Self::new(0, 0, rl.get_screen_width(), rl.get_screen_height())
+    }
+
+    pub fn right(&self) -> i32 {
+        self.left + self.width
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.top + self.height
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        (self.left..self.right()).contains(&x) && (self.top..self.bottom()).contains(&y)
+    }
+
+    /// The rectangle left inside `edges`, never smaller than nothing
+    pub fn shrink(&self, edges: Edges) -> Self {
+        Self::new(
+            self.left + edges.left,
+            self.top + edges.top,
+            (self.width - edges.left - edges.right).max(0),
+            (self.height - edges.top - edges.bottom).max(0),
+        )
+    }
+}
+
+/// Space kept on each side, used for both margins and padding
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Edges {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Edges {
+    pub fn all(value: i32) -> Self {
+        Self { left: value, top: value, right: value, bottom: value }
+    }
+
+    pub fn symmetric(horizontal: i32, vertical: i32) -> Self {
+        Self { left: horizontal, top: vertical, right: horizontal, bottom: vertical }
+    }
+
+    pub fn new(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self { left, top, right, bottom }
+    }
+}
+
+/// Where a node sits inside the space given to it, or where the children of a stack go when they
+/// don't fill it
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Align {
+    #[default]
+    Start,
+    Center,
+    End,
+}
+
+impl Align {
+    /// Offset of something `size` long placed in `available`
+    pub(super) fn offset(self, available: i32, size: i32) -> i32 {
+        match self {
+            Align::Start => 0,
+            Align::Center => (available - size) / 2,
+            Align::End => available - size,
+        }
+    }
+}
+
+/// Size of a node along one axis
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Size {
+    Fixed(i32),
+    /// Take a share of the space left over, in proportion to the weight. Across a stack, and
+    /// outside of stacks, this fills the whole space
+    Fill(u32),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+enum Kind {
+    Widget(usize),
+    Stack { direction: Direction, spacing: i32, justify: Align, children: Vec<Layout> },
+    Overlay(Vec<Layout>),
+    Within(fn(Rect) -> Rect, Box<Layout>),
+    Space,
+}
+
+/// Tree of widgets and stacks that computes the rectangle of every widget from the size of its
+/// parent, anchored by `Align` and kept apart by margins, padding and spacing
+pub struct Layout {
+    kind: Kind,
+    width: Size,
+    height: Size,
+    anchor: (Align, Align),
+    margin: Edges,
+    padding: Edges,
+}
+
+impl Layout {
+    fn with_kind(kind: Kind) -> Self {
+        Self {
+            kind,
+            width: Size::Fill(1),
+            height: Size::Fill(1),
+            anchor: (Align::Start, Align::Start),
+            margin: Edges::default(),
+            padding: Edges::default(),
+        }
+    }
+
+    /// The widget `id` of the `Widgets` the layout is applied to
+    pub fn widget(id: usize) -> Self {
+        Self::with_kind(Kind::Widget(id))
+    }
+
+    /// Children placed left to right
+    pub fn row(children: impl IntoIterator<Item = Layout>) -> Self {
+        Self::with_kind(Kind::Stack { direction: Direction::Horizontal, spacing: 0, justify: Align::Start, children: children.into_iter().collect() })
+    }
+
+    /// Children placed top to bottom
+    pub fn column(children: impl IntoIterator<Item = Layout>) -> Self {
+        Self::with_kind(Kind::Stack { direction: Direction::Vertical, spacing: 0, justify: Align::Start, children: children.into_iter().collect() })
+    }
+
+    /// Children placed on top of each other, each anchored in the whole space on its own
+    pub fn overlay(children: impl IntoIterator<Item = Layout>) -> Self {
+        Self::with_kind(Kind::Overlay(children.into_iter().collect()))
+    }
+
+    /// `child` placed inside the area `area` returns for the space of the node rather than that
+    /// space itself, for widgets that follow something drawn by the scene such as the board
+    pub fn within(area: fn(Rect) -> Rect, child: Layout) -> Self {
+        Self::with_kind(Kind::Within(area, Box::new(child)))
+    }
+
+    /// Nothing, to push the other children of a stack apart
+    pub fn space() -> Self {
+        Self::with_kind(Kind::Space)
+    }
+
+    /// `child` centered across `part` out of `whole` of the width, so that it grows and shrinks
+    /// with the window
+    pub fn centered(child: Layout, part: u32, whole: u32) -> Self {
+        // each side takes half of the rest, the weights are doubled to keep those halves whole
+        let side = whole - part;
+        Self::row([Self::space().width(Size::Fill(side)), child.width(Size::Fill(2 * part)), Self::space().width(Size::Fill(side))])
+    }
+
+    pub fn size(mut self, width: i32, height: i32) -> Self {
+        (self.width, self.height) = (Size::Fixed(width), Size::Fixed(height));
+        self
+    }
+
+    pub fn width(mut self, width: Size) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Size) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Where the node sits in its space when it is smaller than it
+    pub fn anchor(mut self, horizontal: Align, vertical: Align) -> Self {
+        self.anchor = (horizontal, vertical);
+        self
+    }
+
+    /// Space kept around the node
+    pub fn margin(mut self, margin: Edges) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Space kept between a stack or overlay and its children
+    pub fn padding(mut self, padding: Edges) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Gap between the children of a stack, shrunk down to nothing when they don't fit otherwise
+    pub fn spacing(mut self, spacing: i32) -> Self {
+        if let Kind::Stack { spacing: value, .. } = &mut self.kind {
+            *value = spacing;
+        }
+        self
+    }
+
+    /// Where the children of a stack go along it when none of them fills the leftover space
+    pub fn justify(mut self, justify: Align) -> Self {
+        if let Kind::Stack { justify: value, .. } = &mut self.kind {
+            *value = justify;
+        }
+        self
+    }
+
+    /// Move every widget of the tree to its place inside `parent`
+    pub fn apply(&self, elements: &mut [UIElement], parent: Rect) {
+        let outer = parent.shrink(self.margin);
+        let width = match self.width {
+            Size::Fixed(width) => width,
+            Size::Fill(_) => outer.width,
+        };
+        let height = match self.height {
+            Size::Fixed(height) => height,
+            Size::Fill(_) => outer.height,
+        };
+        let bounds = Rect::new(
+            outer.left + self.anchor.0.offset(outer.width, width),
+            outer.top + self.anchor.1.offset(outer.height, height),
+            width,
+            height,
+        );
+
+        match &self.kind {
+            Kind::Widget(id) => {
+                if let Some(element) = elements.iter_mut().find(|element| element.get_id() == *id) {
+                    element.set_bounds(bounds.left, bounds.top, bounds.width, bounds.height);
+                }
+            },
+            Kind::Stack { direction, spacing, justify, children } => apply_stack(elements, bounds.shrink(self.padding), *direction, *spacing, *justify, children),
+            Kind::Overlay(children) => {
+                for child in children {
+                    child.apply(elements, bounds.shrink(self.padding));
+                }
+            },
+            Kind::Within(area, child) => child.apply(elements, area(bounds)),
+            Kind::Space => {},
+        }
+    }
+
+    /// Space taken along the main axis of the parent stack before filling children are grown
+    fn main_fixed(&self, horizontal: bool) -> i32 {
+        let (size, margins) = if horizontal {
+            (self.width, self.margin.left + self.margin.right)
+        } else {
+            (self.height, self.margin.top + self.margin.bottom)
+        };
+
+        match size {
+            Size::Fixed(size) => size + margins,
+            Size::Fill(_) => margins,
+        }
+    }
+
+    fn main_weight(&self, horizontal: bool) -> u32 {
+        match if horizontal { self.width } else { self.height } {
+            Size::Fixed(_) => 0,
+            Size::Fill(weight) => weight,
+        }
+    }
+}
+
+/// Place the children of a stack one after the other inside `inner`
+fn apply_stack(elements: &mut [UIElement], inner: Rect, direction: Direction, spacing: i32, justify: Align, children: &[Layout]) {
+    for (child, slot) in children.iter().zip(stack_slots(inner, direction, spacing, justify, children)) {
+        child.apply(elements, slot);
+    }
+}
+
+/// Space given to each child of a stack inside `inner`, margins included
+fn stack_slots(inner: Rect, direction: Direction, spacing: i32, justify: Align, children: &[Layout]) -> Vec<Rect> {
+    if children.is_empty() {
+        return vec![];
+    }
+
+    // everything is worked out along the main axis, the cross axis is the full inner size
+    let horizontal = direction == Direction::Horizontal;
+    let available = if horizontal { inner.width } else { inner.height };
+
+    let fixed : i32 = children.iter().map(|child| child.main_fixed(horizontal)).sum();
+    let weights : u32 = children.iter().map(|child| child.main_weight(horizontal)).sum();
+    let gaps = children.len() as i32 - 1;
+
+    // spacing gives way before anything else when the children don't fit
+    let spacing = if gaps > 0 { spacing.min(((available - fixed) / gaps).max(0)) } else { 0 };
+    let free = (available - fixed - gaps * spacing).max(0);
+
+    let mut position = if weights == 0 { justify.offset(available, available - free) } else { 0 };
+    let mut remaining = free;
+    let mut remaining_weight = weights;
+    let mut slots = vec![];
+    for child in children {
+        let mut size = child.main_fixed(horizontal);
+        let weight = child.main_weight(horizontal);
+        if weight > 0 {
+            // the last filling child takes whatever rounding left behind
+            let share = if weight == remaining_weight { remaining } else { free * weight as i32 / weights as i32 };
+            size += share;
+            remaining -= share;
+            remaining_weight -= weight;
+        }
+
+        slots.push(if horizontal {
+            Rect::new(inner.left + position, inner.top, size, inner.height)
+        } else {
+            Rect::new(inner.left, inner.top + position, inner.width, size)
+        });
+        position += size + spacing;
+    }
+
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tops(slots: &[Rect]) -> Vec<(i32, i32)> {
+        slots.iter().map(|slot| (slot.top, slot.height)).collect()
+    }
+
+    fn lefts(slots: &[Rect]) -> Vec<(i32, i32)> {
+        slots.iter().map(|slot| (slot.left, slot.width)).collect()
+    }
+
+    #[test]
+    fn stack_keeps_spacing_between_children() {
+        let children = [10, 20, 10].map(|height| Layout::space().height(Size::Fixed(height)));
+        let slots = stack_slots(Rect::new(5, 10, 100, 100), Direction::Vertical, 5, Align::Start, &children);
+
+        assert_eq!(tops(&slots), [(10, 10), (25, 20), (50, 10)]);
+        assert!(slots.iter().all(|slot| slot.left == 5 && slot.width == 100));
+    }
+
+    #[test]
+    fn stack_drops_spacing_when_children_do_not_fit() {
+        let children = [40, 40, 40].map(|height| Layout::space().height(Size::Fixed(height)));
+        let slots = stack_slots(Rect::new(0, 0, 100, 100), Direction::Vertical, 10, Align::Start, &children);
+
+        assert_eq!(tops(&slots), [(0, 40), (40, 40), (80, 40)]);
+    }
+
+    #[test]
+    fn stack_counts_margins_as_fixed_space() {
+        let children = [
+            Layout::space().width(Size::Fixed(20)).margin(Edges::symmetric(5, 0)),
+            Layout::space().width(Size::Fill(1)),
+        ];
+        let slots = stack_slots(Rect::new(0, 0, 100, 10), Direction::Horizontal, 0, Align::Start, &children);
+
+        assert_eq!(lefts(&slots), [(0, 30), (30, 70)]);
+    }
+
+    #[test]
+    fn stack_shares_leftover_space_by_weight() {
+        let children = [
+            Layout::space().width(Size::Fixed(20)),
+            Layout::space().width(Size::Fill(1)),
+            Layout::space().width(Size::Fill(2)),
+        ];
+        let slots = stack_slots(Rect::new(0, 0, 100, 10), Direction::Horizontal, 0, Align::Start, &children);
+
+        // the last filling child takes the pixel lost to rounding
+        assert_eq!(lefts(&slots), [(0, 20), (20, 26), (46, 54)]);
+    }
+
+    #[test]
+    fn centered_takes_its_part_of_the_width() {
+        let Kind::Stack { direction, spacing, justify, children } = Layout::centered(Layout::space(), 5, 9).kind else {
+            panic!("centered should be a row");
+        };
+        let slots = stack_slots(Rect::new(0, 0, 900, 10), direction, spacing, justify, &children);
+
+        assert_eq!(lefts(&slots), [(0, 200), (200, 500), (700, 200)]);
+    }
+
+    #[test]
+    fn stack_justifies_children_that_do_not_fill_it() {
+        let children = [20, 20].map(|width| Layout::space().width(Size::Fixed(width)));
+        let inner = Rect::new(0, 0, 100, 10);
+
+        let start = stack_slots(inner, Direction::Horizontal, 10, Align::Start, &children);
+        let center = stack_slots(inner, Direction::Horizontal, 10, Align::Center, &children);
+        let end = stack_slots(inner, Direction::Horizontal, 10, Align::End, &children);
+
+        assert_eq!(lefts(&start), [(0, 20), (30, 20)]);
+        assert_eq!(lefts(&center), [(25, 20), (55, 20)]);
+        assert_eq!(lefts(&end), [(50, 20), (80, 20)]);
+    }
+}
@@ -0,0 +1,191 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use super::{draw_focus, draw_text, layout::Rect, measure, next_id, UIElementTrait};
+
+const TEXT_PADDING : i32 = 10;
+const SCROLLBAR_WIDTH : i32 = 6;
+const BORDER_COLOR : u32 = 0xff_ff_ff_a0;
+const HOVERED_ROW_COLOR : u32 = 0xff_ff_ff_30;
+const SELECTED_ROW_COLOR : u32 = 0xff_ff_ff_50;
+const SCROLLBAR_COLOR : u32 = 0xff_ff_ff_80;
+
+/// Rows of text scrolled with the mouse wheel, clicking a row selects it
+pub struct ListView {
+    id: usize,
+    enabled: bool,
+    bounds: Rect,
+
+    items: Vec<String>,
+    selected: Option<usize>,
+    // index of the first visible row
+    scroll: usize,
+    hovered: Option<usize>,
+    is_focused: bool,
+
+    font: WeakFont,
+    text_font_size: i32,
+    row_height: i32,
+    empty_text: &'static str,
+}
+
+impl UIElementTrait for ListView {
+    /// True when a row was clicked, or Enter was pressed with the focus on a selected row
+    fn update(&mut self, rl: &mut RaylibHandle) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        if self.is_focused && !self.items.is_empty() {
+            if rl.is_key_pressed(KeyboardKey::KEY_UP) {
+                self.select(self.selected.map_or(0, |index| index.saturating_sub(1)));
+            } else if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+                self.select(self.selected.map_or(0, |index| index + 1));
+            } else if rl.is_key_pressed(KeyboardKey::KEY_ENTER) && self.selected.is_some() {
+                return true;
+            }
+        }
+
+        let (x, y) = (rl.get_mouse_x(), rl.get_mouse_y());
+        self.hovered = None;
+        if !self.bounds.contains(x, y) {
+            return false;
+        }
+
+        let wheel = rl.get_mouse_wheel_move();
+        if wheel < 0.0 {
+            self.scroll = (self.scroll + 1).min(self.max_scroll());
+        } else if wheel > 0.0 {
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+
+        let index = self.scroll + ((y - self.bounds.top) / self.row_height) as usize;
+        if index < self.items.len() {
+            self.hovered = Some(index);
+            if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+                self.selected = Some(index);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn draw(&self, draw_handler: &mut RaylibDrawHandle) {
+        if !self.enabled {
+            return;
+        }
+
+        let bounds = self.bounds;
+        if self.items.is_empty() {
+            draw_text(draw_handler, &self.font, self.empty_text, bounds.left + TEXT_PADDING, bounds.top + TEXT_PADDING, self.text_font_size, RayColor::GRAY);
+        }
+
+        for (row, item) in self.items.iter().enumerate().skip(self.scroll).take(self.visible_rows()) {
+            let top = bounds.top + (row - self.scroll) as i32 * self.row_height;
+            let color = if self.selected == Some(row) {
+                Some(SELECTED_ROW_COLOR)
+            } else if self.hovered == Some(row) {
+                Some(HOVERED_ROW_COLOR)
+            } else {
+                None
+            };
+            if let Some(color) = color {
+                draw_handler.draw_rectangle(bounds.left, top, bounds.width, self.row_height, RayColor::get_color(color));
+            }
+
+            let (_, text_height) = measure(&self.font, item, self.text_font_size);
+            draw_text(draw_handler, &self.font, item, bounds.left + TEXT_PADDING, top + (self.row_height - text_height) / 2, self.text_font_size, RayColor::WHITE);
+        }
+
+        // the thumb is as much of the bar as the visible rows are of all rows
+        let max_scroll = self.max_scroll();
+        if max_scroll > 0 {
+            let thumb_height = (bounds.height * self.visible_rows() as i32 / self.items.len() as i32).max(SCROLLBAR_WIDTH);
+            let thumb_top = bounds.top + (bounds.height - thumb_height) * self.scroll as i32 / max_scroll as i32;
+            draw_handler.draw_rectangle(bounds.right() - SCROLLBAR_WIDTH, thumb_top, SCROLLBAR_WIDTH, thumb_height, RayColor::get_color(SCROLLBAR_COLOR));
+        }
+
+        draw_handler.draw_rectangle_lines(bounds.left, bounds.top, bounds.width, bounds.height, RayColor::get_color(BORDER_COLOR));
+        if self.is_focused {
+            draw_focus(draw_handler, bounds.left, bounds.top, bounds.width, bounds.height);
+        }
+    }
+
+    fn get_id(&self) -> usize {
+        self.id
+    }
+}
+
+impl ListView {
+    pub fn new(font: WeakFont, text_font_size: i32, row_height: i32) -> Self {
+        Self {
+            id: next_id(),
+            enabled: true,
+            bounds: Rect::default(),
+            items: vec![],
+            selected: None,
+            scroll: 0,
+            hovered: None,
+            is_focused: false,
+            font, text_font_size, row_height,
+            empty_text: "",
+        }
+    }
+
+    pub fn set_enabled(&mut self, value: bool) {
+        self.enabled = value;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_focused(&mut self, value: bool) {
+        self.is_focused = value;
+    }
+
+    /// Replace the rows, dropping the selection
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        self.selected = None;
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    pub fn get_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn selected_item(&self) -> Option<&String> {
+        self.selected.and_then(|index| self.items.get(index))
+    }
+
+    /// Text shown in place of the rows when there are none
+    pub fn set_empty_text(&mut self, text: &'static str) {
+        self.empty_text = text;
+    }
+
+    /// Set by the layout of the scene, as many rows as fit are shown
+    pub fn set_bounds(&mut self, left: i32, top: i32, width: i32, height: i32) {
+        self.bounds = Rect::new(left, top, width, height);
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    /// Select the row `index`, or the last one past the end, scrolling it into view
+    fn select(&mut self, index: usize) {
+        let index = index.min(self.items.len().saturating_sub(1));
+        self.selected = Some(index);
+        if index < self.scroll {
+            self.scroll = index;
+        } else if index >= self.scroll + self.visible_rows() {
+            self.scroll = (index + 1).saturating_sub(self.visible_rows());
+        }
+    }
+
+    fn visible_rows(&self) -> usize {
+        (self.bounds.height / self.row_height).max(0) as usize
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.items.len().saturating_sub(self.visible_rows())
+    }
+}
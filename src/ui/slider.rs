@@ -0,0 +1,130 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use super::{draw_focus, layout::Rect, next_id, UIElementTrait, HOVERED_COLOR, NORMAL_COLOR};
+
+const TRACK_HEIGHT : i32 = 6;
+const KNOB_WIDTH : i32 = 14;
+
+/// Value between a minimum and a maximum picked by dragging a knob along a track
+pub struct Slider {
+    id: usize,
+    enabled: bool,
+    bounds: Rect,
+
+    min: f32,
+    max: f32,
+    // values are rounded to a multiple of the step above the minimum, unless it is 0
+    step: f32,
+    value: f32,
+
+    dragging: bool,
+    is_hovering: bool,
+    is_focused: bool,
+    normal_color: RayColor,
+    hovered_color: RayColor,
+}
+
+impl UIElementTrait for Slider {
+    /// True when the value was changed by dragging
+    fn update(&mut self, rl: &mut RaylibHandle) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let x = rl.get_mouse_x();
+        self.is_hovering = self.bounds.contains(x, rl.get_mouse_y());
+        if self.is_hovering && rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.dragging = true;
+        }
+        if !rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.dragging = false;
+        }
+        if !self.dragging {
+            // with the focus the arrows move the knob a step, or a tenth of the track without steps
+            let step = if self.step > 0.0 { self.step } else { (self.max - self.min) / 10.0 };
+            let old = self.value;
+            if self.is_focused && rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+                self.set_value(self.value - step);
+            } else if self.is_focused && rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+                self.set_value(self.value + step);
+            }
+            return self.value != old;
+        }
+
+        let track = (self.bounds.width - KNOB_WIDTH).max(1);
+        let fraction = ((x - self.bounds.left - KNOB_WIDTH / 2) as f32 / track as f32).clamp(0.0, 1.0);
+        let old = self.value;
+        self.set_value(self.min + fraction * (self.max - self.min));
+        self.value != old
+    }
+
+    fn draw(&self, draw_handler: &mut RaylibDrawHandle) {
+        if !self.enabled {
+            return;
+        }
+
+        let color = if self.is_hovering || self.dragging { self.hovered_color } else { self.normal_color };
+        let track_y = self.bounds.top + (self.bounds.height - TRACK_HEIGHT) / 2;
+        draw_handler.draw_rectangle(self.bounds.left, track_y, self.bounds.width, TRACK_HEIGHT, self.normal_color);
+
+        let knob_x = self.bounds.left + ((self.bounds.width - KNOB_WIDTH) as f32 * self.fraction()) as i32;
+        draw_handler.draw_rectangle(self.bounds.left, track_y, knob_x - self.bounds.left, TRACK_HEIGHT, color);
+        draw_handler.draw_rectangle(knob_x, self.bounds.top, KNOB_WIDTH, self.bounds.height, color);
+        if self.is_focused {
+            draw_focus(draw_handler, self.bounds.left, self.bounds.top, self.bounds.width, self.bounds.height);
+        }
+    }
+
+    fn get_id(&self) -> usize {
+        self.id
+    }
+}
+
+impl Slider {
+    pub fn new(min: f32, max: f32, step: f32) -> Self {
+        Self {
+            id: next_id(),
+            enabled: true,
+            bounds: Rect::default(),
+            min, max, step,
+            value: min,
+            dragging: false,
+            is_hovering: false,
+            is_focused: false,
+            normal_color: RayColor::get_color(NORMAL_COLOR),
+            hovered_color: RayColor::get_color(HOVERED_COLOR),
+        }
+    }
+
+    pub fn set_enabled(&mut self, value: bool) {
+        self.enabled = value;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_focused(&mut self, value: bool) {
+        self.is_focused = value;
+    }
+
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+
+    /// Set the value, kept between the minimum and maximum and rounded to the step
+    pub fn set_value(&mut self, value: f32) {
+        let value = if self.step > 0.0 { self.min + ((value - self.min) / self.step).round() * self.step } else { value };
+        self.value = value.clamp(self.min, self.max);
+    }
+
+    fn fraction(&self) -> f32 {
+        if self.max > self.min { (self.value - self.min) / (self.max - self.min) } else { 0.0 }
+    }
+
+    /// Set by the layout of the scene
+    pub fn set_bounds(&mut self, left: i32, top: i32, width: i32, height: i32) {
+        self.bounds = Rect::new(left, top, width, height);
+    }
+}
@@ -0,0 +1,113 @@
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use super::{draw_text, layout::{Align, Rect}, measure};
+
+/// Text drawn inside a rectangle, split on line breaks and, when wrapping, between words that
+/// would go past its right side. The lines are aligned in the rectangle along both axes
+pub struct TextBlock {
+    text: String,
+    font: WeakFont,
+    font_size: i32,
+    wrap: bool,
+    align: (Align, Align),
+    bounds: Rect,
+    // lines as they fit in the bounds, with their widths
+    lines: Vec<(String, i32)>,
+}
+
+impl TextBlock {
+    /// Empty text, centered and not wrapped
+    pub fn new(font: WeakFont, font_size: i32) -> Self {
+        Self {
+            text: String::new(),
+            font, font_size,
+            wrap: false,
+            align: (Align::Center, Align::Center),
+            bounds: Rect::default(),
+            lines: vec![],
+        }
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if text != self.text {
+            self.text = text;
+            self.break_lines();
+        }
+    }
+
+    pub fn set_font_size(&mut self, font_size: i32) {
+        self.font_size = font_size;
+        self.break_lines();
+    }
+
+    pub fn set_wrap(&mut self, value: bool) {
+        self.wrap = value;
+        self.break_lines();
+    }
+
+    pub fn set_align(&mut self, horizontal: Align, vertical: Align) {
+        self.align = (horizontal, vertical);
+    }
+
+    pub fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+        self.break_lines();
+    }
+
+    /// Height of all the lines as they fit in the width of the bounds
+    pub fn height(&self) -> i32 {
+        self.lines.len() as i32 * (self.line_height() + self.line_gap()) - self.line_gap()
+    }
+
+    pub fn draw(&self, draw_handler: &mut RaylibDrawHandle, color: RayColor) {
+        let step = self.line_height() + self.line_gap();
+        let mut y = self.bounds.top + self.align.1.offset(self.bounds.height, self.height());
+        for (line, width) in &self.lines {
+            let x = self.bounds.left + self.align.0.offset(self.bounds.width, *width);
+            draw_text(draw_handler, &self.font, line, x, y, self.font_size, color);
+            y += step;
+        }
+    }
+
+    fn line_height(&self) -> i32 {
+        measure(&self.font, "", self.font_size).1
+    }
+
+    fn line_gap(&self) -> i32 {
+        self.font_size / 4
+    }
+
+    fn break_lines(&mut self) {
+        self.lines.clear();
+        let text = self.text.clone();
+        for paragraph in text.split('\n') {
+            if !self.wrap {
+                self.push_line(paragraph.to_string());
+                continue;
+            }
+
+            // a word wider than the bounds is left on a line of its own rather than cut
+            let mut line = String::new();
+            for word in paragraph.split(' ') {
+                let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+                if !line.is_empty() && measure(&self.font, &candidate, self.font_size).0 > self.bounds.width {
+                    self.push_line(std::mem::replace(&mut line, word.to_string()));
+                } else {
+                    line = candidate;
+                }
+            }
+            self.push_line(line);
+        }
+    }
+
+    fn push_line(&mut self, line: String) {
+        let (width, _) = measure(&self.font, &line, self.font_size);
+        self.lines.push((line, width));
+    }
+}
@@ -0,0 +1,159 @@
+use std::{cell::{Cell, RefCell}, rc::Rc};
+
+use raylib::prelude::*;
+use raylib::color::Color as RayColor;
+
+use crate::resources::Resources;
+
+use super::{animate::{Animate, Easing, Sequence, Tween}, layout::{Align, Edges, Rect}, text::TextBlock};
+
+const MAX_TOASTS : usize = 5;
+const TOAST_WIDTH : i32 = 340;
+const FONT_SIZE : i32 = 18;
+const TEXT_PADDING : i32 = 10;
+const TOAST_SPACING : i32 = 8;
+const WINDOW_PADDING : i32 = 12;
+const STRIPE_WIDTH : i32 = 6;
+const BACKGROUND_COLOR : u32 = 0x20_20_20_e8;
+// Time a toast takes to slide in from the right edge of the window
+const SLIDE_SECONDS : f32 = 0.3;
+// Time over which a toast fades out before it is removed
+const FADE_SECONDS : f32 = 0.4;
+
+thread_local! {
+    // Toasts on screen, oldest first, pushed from any scene or player. They hold the font they are
+    // drawn with, which stays on the thread of the window
+    static TOASTS : RefCell<Vec<Toast>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Seconds a toast stays, errors long enough to be read
+    fn duration(&self) -> f32 {
+        match self {
+            Severity::Info => 3.0,
+            Severity::Warning => 5.0,
+            Severity::Error => 8.0,
+        }
+    }
+
+    fn color(&self) -> u32 {
+        match self {
+            Severity::Info => 0x42_a5_f5_ff,
+            Severity::Warning => 0xf5_c0_42_ff,
+            Severity::Error => 0xf5_57_42_ff,
+        }
+    }
+}
+
+/// Short message shown in the corner of the window over every scene until it times out or is
+/// clicked
+struct Toast {
+    severity: Severity,
+    message: String,
+    // slides in, waits and fades out, the toast is removed once it finished
+    animation: Sequence,
+    // distance left to slide, as a fraction of the width of the toast and the window padding
+    slide: Rc<Cell<f32>>,
+    alpha: Rc<Cell<f32>>,
+    // laid out on the first update after the toast is pushed, which has a font to measure with
+    text: Option<TextBlock>,
+    bounds: Rect,
+}
+
+pub fn info(message: impl Into<String>) {
+    push(Severity::Info, message);
+}
+
+pub fn warning(message: impl Into<String>) {
+    push(Severity::Warning, message);
+}
+
+pub fn error(message: impl Into<String>) {
+    push(Severity::Error, message);
+}
+
+/// Show `message` under the toasts already on screen, dropping the oldest when there are too many
+pub fn push(severity: Severity, message: impl Into<String>) {
+    let slide = Tween::new(1.0, 0.0, SLIDE_SECONDS).easing(Easing::OutBack);
+    let fade = Tween::new(1.0, 0.0, FADE_SECONDS).easing(Easing::InQuad).delay(severity.duration() - SLIDE_SECONDS - FADE_SECONDS);
+    let (slide_output, alpha) = (slide.output(), fade.output());
+    let animation = Sequence::new(vec![Box::new(slide), Box::new(fade)]);
+
+    TOASTS.with_borrow_mut(|toasts| {
+        toasts.push(Toast { severity, message: message.into(), animation, slide: slide_output, alpha, text: None, bounds: Rect::default() });
+        if toasts.len() > MAX_TOASTS {
+            toasts.remove(0);
+        }
+    });
+}
+
+/// Remove the toasts that timed out or were clicked and stack the others down from the top right
+/// corner. Called once per frame before the scene sees the mouse, returns true when a click
+/// dismissed a toast so that the scene can ignore it
+pub fn update(rl: &mut RaylibHandle, thread: &RaylibThread, resources: &Resources) -> bool {
+    // fetched before the toasts are borrowed, a font that fails to load pushes a toast of its own
+    let font = resources.ui_font(rl, thread, FONT_SIZE);
+    TOASTS.with_borrow_mut(|toasts| update_toasts(rl, font, toasts))
+}
+
+fn update_toasts(rl: &RaylibHandle, font: WeakFont, toasts: &mut Vec<Toast>) -> bool {
+    toasts.iter_mut().for_each(|toast| { toast.animation.advance(rl.get_frame_time()); });
+    toasts.retain(|toast| !toast.animation.is_finished());
+
+    let mut top = WINDOW_PADDING;
+    for toast in toasts.iter_mut() {
+        let slide = (toast.slide.get() * (TOAST_WIDTH + WINDOW_PADDING) as f32) as i32;
+        let left = rl.get_screen_width() - WINDOW_PADDING - TOAST_WIDTH + slide;
+        let text = toast.text.get_or_insert_with(|| {
+            let mut text = TextBlock::new(font.clone(), FONT_SIZE);
+            text.set_wrap(true);
+            text.set_align(Align::Start, Align::Start);
+            text.set_text(toast.message.as_str());
+            text
+        });
+
+        let inner = Rect::new(left, top, TOAST_WIDTH, 0).shrink(Edges::new(STRIPE_WIDTH + TEXT_PADDING, TEXT_PADDING, TEXT_PADDING, 0));
+        text.set_bounds(inner);
+        toast.bounds = Rect::new(left, top, TOAST_WIDTH, text.height() + 2 * TEXT_PADDING);
+        top = toast.bounds.bottom() + TOAST_SPACING;
+    }
+
+    if !rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+        return false;
+    }
+
+    let (x, y) = (rl.get_mouse_x(), rl.get_mouse_y());
+    match toasts.iter().position(|toast| toast.bounds.contains(x, y)) {
+        Some(index) => {
+            toasts.remove(index);
+            true
+        },
+        None => false,
+    }
+}
+
+/// Draw the toasts over whatever the scene drew, fading them out at the end
+pub fn draw(draw_handler: &mut RaylibDrawHandle) {
+    TOASTS.with_borrow(|toasts| draw_toasts(draw_handler, toasts));
+}
+
+fn draw_toasts(draw_handler: &mut RaylibDrawHandle, toasts: &[Toast]) {
+    for toast in toasts {
+        let Some(text) = &toast.text else {
+            continue;
+        };
+
+        let alpha = toast.alpha.get().clamp(0.0, 1.0);
+        let bounds = toast.bounds;
+        draw_handler.draw_rectangle(bounds.left, bounds.top, bounds.width, bounds.height, RayColor::get_color(BACKGROUND_COLOR).fade(alpha * 0.9));
+        draw_handler.draw_rectangle(bounds.left, bounds.top, STRIPE_WIDTH, bounds.height, RayColor::get_color(toast.severity.color()).fade(alpha));
+        text.draw(draw_handler, RayColor::WHITE.fade(alpha));
+    }
+}
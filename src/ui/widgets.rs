@@ -0,0 +1,262 @@
+use std::{collections::HashMap, rc::Rc};
+
+use raylib::prelude::*;
+
+use crate::scenes::SceneInitType;
+
+use super::{button::Button, checkbox::Checkbox, dropdown::Dropdown, input::Input, label::Label, layout::{Layout, Rect}, list::ListView, slider::Slider, UIElement, UIElementTrait};
+
+/// What happened to a widget during an update
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Event {
+    /// A button or a row of a list was pressed
+    Clicked,
+    /// Enter was pressed in an input
+    Submitted,
+    /// The text of an input was edited, a checkbox ticked, or the value of a dropdown or slider
+    /// picked
+    Changed,
+}
+
+/// Called with the scene that owns the widgets when the event it was registered for happens
+pub type Callback<S> = Rc<dyn Fn(&mut S, &mut RaylibHandle) -> SceneInitType>;
+
+/// Widgets of a scene, drawn and updated in the order they were added. The events of the widgets
+/// are handed to the callbacks registered for them, and their layout is applied again whenever
+/// the window changes size. Tab and Shift+Tab move the keyboard focus through the widgets in the
+/// same order
+pub struct Widgets<S> {
+    elements: Vec<UIElement>,
+    callbacks: HashMap<(usize, Event), Callback<S>>,
+    layout: Option<Layout>,
+    // window the layout was last applied to
+    laid_out: Option<Rect>,
+    // widget with the keyboard focus
+    focused: Option<usize>,
+}
+
+impl<S> Default for Widgets<S> {
+    fn default() -> Self {
+        Self { elements: vec![], callbacks: HashMap::new(), layout: None, laid_out: None, focused: None }
+    }
+}
+
+impl<S> Widgets<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take ownership of a widget, returning its id
+    pub fn add(&mut self, element: impl Into<UIElement>) -> usize {
+        let element = element.into();
+        let id = element.get_id();
+        self.elements.push(element);
+        id
+    }
+
+    /// Call `callback` every time the widget `id` reports `event`
+    pub fn on(&mut self, id: usize, event: Event, callback: impl Fn(&mut S, &mut RaylibHandle) -> SceneInitType + 'static) {
+        self.callbacks.insert((id, event), Rc::new(callback));
+    }
+
+    /// Place the widgets with `layout` from now on, widgets it leaves out keep their bounds
+    pub fn set_layout(&mut self, rl: &RaylibHandle, layout: Layout) {
+        self.layout = Some(layout);
+        self.laid_out = None;
+        self.relayout(Rect::window(rl));
+    }
+
+    /// Apply the layout if the window changed size since it was last applied
+    pub fn relayout(&mut self, window: Rect) {
+        if self.laid_out == Some(window) {
+            return;
+        }
+
+        if let Some(layout) = &self.layout {
+            layout.apply(&mut self.elements, window);
+            self.laid_out = Some(window);
+        }
+    }
+
+    /// Give the keyboard focus to the widget `id`, such as the input a scene is mostly about
+    pub fn focus(&mut self, id: usize) {
+        self.set_focus(Some(id));
+    }
+
+    /// Whether a widget has the keyboard focus, and so takes the keys it acts on such as Space
+    pub fn has_focus(&self) -> bool {
+        self.focused.is_some()
+    }
+
+    fn set_focus(&mut self, id: Option<usize>) {
+        self.focused = id;
+        for element in &mut self.elements {
+            element.set_focused(Some(element.get_id()) == id);
+        }
+    }
+
+    /// Move the focus to the next focusable widget, or the previous one when `backwards`, going
+    /// around at the ends
+    fn move_focus(&mut self, backwards: bool) {
+        let focusable : Vec<usize> = self.elements.iter().filter(|element| element.is_focusable()).map(|element| element.get_id()).collect();
+        if focusable.is_empty() {
+            return;
+        }
+
+        let count = focusable.len();
+        let next = match self.focused.and_then(|id| focusable.iter().position(|&focusable| focusable == id)) {
+            Some(index) if backwards => (index + count - 1) % count,
+            Some(index) => (index + 1) % count,
+            None if backwards => count - 1,
+            None => 0,
+        };
+        self.set_focus(Some(focusable[next]));
+    }
+
+    pub fn get(&self, id: usize) -> Option<&UIElement> {
+        self.elements.iter().find(|element| element.get_id() == id)
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut UIElement> {
+        self.elements.iter_mut().find(|element| element.get_id() == id)
+    }
+
+    pub fn button_mut(&mut self, id: usize) -> Option<&mut Button> {
+        match self.get_mut(id) {
+            Some(UIElement::Button(button)) => Some(button),
+            _ => None,
+        }
+    }
+
+    pub fn input(&self, id: usize) -> Option<&Input> {
+        match self.get(id) {
+            Some(UIElement::Input(input)) => Some(input),
+            _ => None,
+        }
+    }
+
+    pub fn input_mut(&mut self, id: usize) -> Option<&mut Input> {
+        match self.get_mut(id) {
+            Some(UIElement::Input(input)) => Some(input),
+            _ => None,
+        }
+    }
+
+    pub fn label_mut(&mut self, id: usize) -> Option<&mut Label> {
+        match self.get_mut(id) {
+            Some(UIElement::Label(label)) => Some(label),
+            _ => None,
+        }
+    }
+
+    pub fn checkbox(&self, id: usize) -> Option<&Checkbox> {
+        match self.get(id) {
+            Some(UIElement::Checkbox(checkbox)) => Some(checkbox),
+            _ => None,
+        }
+    }
+
+    pub fn dropdown(&self, id: usize) -> Option<&Dropdown> {
+        match self.get(id) {
+            Some(UIElement::Dropdown(dropdown)) => Some(dropdown),
+            _ => None,
+        }
+    }
+
+    pub fn list(&self, id: usize) -> Option<&ListView> {
+        match self.get(id) {
+            Some(UIElement::List(list)) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn list_mut(&mut self, id: usize) -> Option<&mut ListView> {
+        match self.get_mut(id) {
+            Some(UIElement::List(list)) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn slider(&self, id: usize) -> Option<&Slider> {
+        match self.get(id) {
+            Some(UIElement::Slider(slider)) => Some(slider),
+            _ => None,
+        }
+    }
+
+    /// Text of the input `id`, empty if there is no such input
+    pub fn text(&self, id: usize) -> String {
+        self.input(id).map(|input| input.get_text().clone()).unwrap_or_default()
+    }
+
+    /// Draw every widget, an open dropdown last so that it covers the others
+    pub fn draw(&self, draw_handler: &mut RaylibDrawHandle) {
+        for element in self.elements.iter().filter(|element| !element.captures_input()) {
+            element.draw(draw_handler);
+        }
+        for element in self.elements.iter().filter(|element| element.captures_input()) {
+            element.draw(draw_handler);
+        }
+    }
+
+    /// Update every widget, returning the callbacks of the events that happened in the order of
+    /// the widgets. While a dropdown is open it is the only one updated
+    pub fn update(&mut self, rl: &mut RaylibHandle) -> Vec<Callback<S>> {
+        self.relayout(Rect::window(rl));
+
+        let mut events = vec![];
+
+        let capturing = self.elements.iter().any(|element| element.captures_input());
+        if !capturing && rl.is_key_pressed(KeyboardKey::KEY_TAB) {
+            self.move_focus(rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT));
+        }
+
+        for element in self.elements.iter_mut().filter(|element| !capturing || element.captures_input()) {
+            let id = element.get_id();
+            match element {
+                UIElement::Input(input) => {
+                    let text = input.get_text().clone();
+                    let submitted = input.update(rl);
+                    if *input.get_text() != text {
+                        events.push((id, Event::Changed));
+                    }
+                    if submitted {
+                        events.push((id, Event::Submitted));
+                    }
+                },
+                UIElement::Checkbox(_) | UIElement::Dropdown(_) | UIElement::Slider(_) => if element.update(rl) {
+                    events.push((id, Event::Changed));
+                },
+                element => if element.update(rl) {
+                    events.push((id, Event::Clicked));
+                },
+            }
+        }
+
+        // a click takes the focus away from the keyboard, unless it selected an input
+        if !capturing && rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            let clicked = self.elements.iter().find_map(|element| match element {
+                UIElement::Input(input) if input.is_selected() => Some(input.get_id()),
+                _ => None,
+            });
+            if clicked != self.focused {
+                self.set_focus(clicked);
+            }
+        }
+
+        events.into_iter().filter_map(|event| self.callbacks.get(&event).cloned()).collect()
+    }
+}
+
+/// Run the callbacks returned by `Widgets::update` on the scene, stopping at the first one that
+/// changes the scene
+pub fn dispatch<S>(scene: &mut S, rl: &mut RaylibHandle, callbacks: Vec<Callback<S>>) -> SceneInitType {
+    for callback in callbacks {
+        let next = callback(scene, rl);
+        if !matches!(next, SceneInitType::None) {
+            return next;
+        }
+    }
+
+    SceneInitType::None
+}